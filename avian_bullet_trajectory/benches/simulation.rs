@@ -0,0 +1,261 @@
+//! Benchmarks for the bullet simulation against a map-sized set of colliders.
+//!
+//! Run with `cargo bench -p avian_bullet_trajectory`. Full-auto fire at 900 rounds per minute
+//! lands a shot every few frames at 60 fps, so each benchmark here should stay well under a
+//! millisecond to leave the rest of the frame alone.
+
+use std::hint::black_box;
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletSpatialQuery, BulletTrajectory, CollisionWorld, SimpleCollider,
+    SimpleCollisionWorld, SimpleShape, SpatialQueryWorld, SpreadPattern, TrajectoryRecording,
+    Zeroing, simulate_trajectory,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::SystemState, prelude::*};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+
+/// Centers and sizes of the 400 crates and pillars on the map
+fn crates() -> impl Iterator<Item = (Vec3, Vec3)> {
+    (0..20).flat_map(|column| {
+        (0..20).map(move |row| {
+            let height = 2.0 + ((column * 7 + row * 13) % 10) as f32;
+            (
+                Vec3::new(
+                    -475.0 + column as f32 * 50.0,
+                    height / 2.0,
+                    -25.0 - row as f32 * 50.0,
+                ),
+                Vec3::new(4.0, height, 4.0),
+            )
+        })
+    })
+}
+
+/// A kilometer of ground with 400 crates and pillars on it, and a clear lane down -Z
+fn map_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        bevy::transform::TransformPlugin,
+        AssetPlugin::default(),
+        PhysicsPlugins::default(),
+    ))
+    .init_asset::<Mesh>();
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    world.spawn((
+        RigidBody::Static,
+        Collider::cuboid(1000.0, 1.0, 1000.0),
+        Transform::from_xyz(0.0, -0.5, -500.0),
+    ));
+    for (center, size) in crates() {
+        world.spawn((
+            RigidBody::Static,
+            Collider::cuboid(size.x, size.y, size.z),
+            Transform::from_translation(center),
+        ));
+    }
+    app.update();
+    app
+}
+
+/// The same map as [`map_app`] as plain boxes, to tell the cost of avian's queries apart
+fn simple_map() -> SimpleCollisionWorld {
+    let mut entities = World::new();
+    let ground = SimpleCollider::new(
+        entities.spawn_empty().id(),
+        SimpleShape::Aabb {
+            min: Vec3::new(-500.0, -1.0, -1000.0),
+            max: Vec3::new(500.0, 0.0, 0.0),
+        },
+    );
+    let crates = crates().map(|(center, size)| {
+        SimpleCollider::new(
+            entities.spawn_empty().id(),
+            SimpleShape::Aabb {
+                min: center - size / 2.0,
+                max: center + size / 2.0,
+            },
+        )
+    });
+    SimpleCollisionWorld::new([ground].into_iter().chain(crates))
+}
+
+fn simulation(c: &mut Criterion) {
+    let mut app = map_app();
+    let mut state = SystemState::<BulletSpatialQuery>::new(app.world_mut());
+    let spatial_query = state.get(app.world());
+    let filter = SpatialQueryFilter::default();
+
+    // A crate about 33 m away
+    let pistol = BulletPhysicsConfig::caliber_9mm();
+    let at_crate = Vec3::new(1.0, 0.0, -1.0).normalize();
+    c.bench_function("hitscan_short_range", |b| {
+        b.iter(|| {
+            spatial_query.simulate_bullet_trajectory(
+                black_box(START),
+                black_box(at_crate * 375.0),
+                0.0075,
+                Some(pistol.clone()),
+                &filter,
+            )
+        })
+    });
+
+    // Up the clear lane and off the end of the map, flying for the full 30 s
+    let mut group = c.benchmark_group("long_range_miss");
+    let rifle = BulletPhysicsConfig::caliber_556();
+    let fine = BulletPhysicsConfig {
+        broad_phase_steps: 0,
+        ..rifle.clone()
+    };
+    let skyward = Vec3::new(0.0, 0.3, -1.0).normalize();
+    for (name, config) in [("broad_phase", &rifle), ("every_step", &fine)] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                spatial_query.simulate_bullet_trajectory(
+                    black_box(START),
+                    black_box(skyward * 900.0),
+                    0.004,
+                    Some(config.clone()),
+                    &filter,
+                )
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("pellet_batch_12");
+    let shotgun = BulletPhysicsConfig::buckshot_00();
+    let spread = SpreadPattern::new(0.05, 12, 7);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            spatial_query.simulate_spread(
+                black_box(START),
+                black_box(at_crate * 400.0),
+                0.0035,
+                &spread,
+                Some(shotgun.clone()),
+                &filter,
+                None,
+            )
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            spatial_query.par_simulate_spread(
+                black_box(START),
+                black_box(at_crate * 400.0),
+                0.0035,
+                &spread,
+                Some(shotgun.clone()),
+                &filter,
+                None,
+            )
+        })
+    });
+    group.finish();
+}
+
+/// A second of full-auto from the F2000 at 850 rounds per minute with the zero cached, each shot
+/// launched later into the air and strayed further by bloom. Returns how many shots hit.
+fn full_auto_second(
+    world: &impl CollisionWorld,
+    zeroing: &Zeroing,
+    sight: &Transform,
+    elevation: f32,
+    config: &BulletPhysicsConfig,
+) -> usize {
+    let interval = 60.0 / 850.0;
+    let (start, direction) = zeroing.launch(sight, elevation);
+    (0..15)
+        .filter(|&shot| {
+            let cone = 0.0015 + (0.003 * shot as f32).min(0.025);
+            let velocity =
+                SpreadPattern::new(cone, 1, shot as u64).velocities(direction * 900.0)[0];
+            let config = BulletPhysicsConfig {
+                launch_time: shot as f32 * interval,
+                ..config.clone()
+            };
+            simulate_trajectory(world, start, velocity, 0.004, &config)
+                .hit_entity
+                .is_some()
+        })
+        .count()
+}
+
+/// Everything a rifle shot costs in the shooter: zeroing, aiming through the sights and flying
+fn per_shot(c: &mut Criterion) {
+    let mut app = map_app();
+    let mut state = SystemState::<BulletSpatialQuery>::new(app.world_mut());
+    let spatial_query = state.get(app.world());
+    let filter = SpatialQueryFilter::default();
+    let simple = simple_map();
+
+    // The F2000: 5.56 at 900 m/s, sights 7 cm over the bore zeroed at 100 m, recording just
+    // enough of the path to draw it
+    let rifle = BulletPhysicsConfig::caliber_556();
+    let fired = BulletPhysicsConfig {
+        recording: TrajectoryRecording::Simplified { tolerance: 0.01 },
+        ..rifle.clone()
+    };
+    let zeroing = Zeroing::new(0.07, 100.0);
+    let elevation = zeroing.elevation(900.0, 0.004, &rifle).unwrap();
+    // Looking at a crate about 127 m away
+    let sight =
+        Transform::from_translation(START).looking_to(Vec3::new(25.0, 0.0, -125.0), Vec3::Y);
+
+    let mut group = c.benchmark_group("per_shot");
+    group.bench_function("solve_zero", |b| {
+        b.iter(|| zeroing.elevation(black_box(900.0), 0.004, &rifle))
+    });
+    group.bench_function("zero_and_fire", |b| {
+        b.iter(|| {
+            let elevation = zeroing.elevation(black_box(900.0), 0.004, &rifle).unwrap();
+            let (start, direction) = zeroing.launch(black_box(&sight), elevation);
+            spatial_query.simulate_bullet_trajectory(
+                start,
+                direction * 900.0,
+                0.004,
+                Some(fired.clone()),
+                &filter,
+            )
+        })
+    });
+    // What the shooter does now the elevation is solved once per weapon
+    group.bench_function("cached_zero", |b| {
+        b.iter(|| {
+            let (start, direction) = zeroing.launch(black_box(&sight), black_box(elevation));
+            spatial_query.simulate_bullet_trajectory(
+                start,
+                direction * 900.0,
+                0.004,
+                Some(fired.clone()),
+                &filter,
+            )
+        })
+    });
+    group.bench_function("cached_zero_simple_world", |b| {
+        b.iter(|| {
+            let (start, direction) = zeroing.launch(black_box(&sight), black_box(elevation));
+            simulate_trajectory(&simple, start, direction * 900.0, 0.004, &fired)
+        })
+    });
+    // A whole second of fire has to fit in the frames it's spread over
+    let world = SpatialQueryWorld::new(&spatial_query, &filter);
+    group.bench_function("full_auto_second", |b| {
+        b.iter(|| full_auto_second(&world, &zeroing, black_box(&sight), elevation, &fired))
+    });
+    group.bench_function("full_auto_second_simple_world", |b| {
+        b.iter(|| full_auto_second(&simple, &zeroing, black_box(&sight), elevation, &fired))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, simulation, per_shot);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+//! Prints a range card for one of the caliber presets.
+//!
+//! ```text
+//! range_card <preset> <mass_kg> <muzzle_speed> [--increment <m>] [--max-range <m>]
+//!     [--zero <sight_height_m>,<zero_distance_m>] [--format csv|json]
+//! ```
+
+use std::{env, process::ExitCode};
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, Zeroing};
+
+const USAGE: &str = "usage: range_card <9mm|556|762|50bmg|buckshot_00> <mass_kg> <muzzle_speed> \
+[--increment <m>] [--max-range <m>] [--zero <sight_height_m>,<zero_distance_m>] \
+[--format csv|json]";
+
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Args {
+    config: BulletPhysicsConfig,
+    mass: f32,
+    muzzle_speed: f32,
+    increment: f32,
+    max_range: f32,
+    zeroing: Option<Zeroing>,
+    format: Format,
+}
+
+fn preset(name: &str) -> Result<BulletPhysicsConfig, String> {
+    match name {
+        "9mm" => Ok(BulletPhysicsConfig::caliber_9mm()),
+        "556" => Ok(BulletPhysicsConfig::caliber_556()),
+        "762" => Ok(BulletPhysicsConfig::caliber_762()),
+        "50bmg" => Ok(BulletPhysicsConfig::caliber_50bmg()),
+        "buckshot_00" => Ok(BulletPhysicsConfig::buckshot_00()),
+        _ => Err(format!("unknown preset `{name}`")),
+    }
+}
+
+fn number(name: &str, value: Option<String>) -> Result<f32, String> {
+    let value = value.ok_or_else(|| format!("missing {name}"))?;
+    value
+        .parse()
+        .map_err(|_| format!("{name} should be a number, got `{value}`"))
+}
+
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let config = preset(&args.next().ok_or("missing preset")?)?;
+    let mut parsed = Args {
+        config,
+        mass: number("mass", args.next())?,
+        muzzle_speed: number("muzzle speed", args.next())?,
+        increment: 25.0,
+        max_range: 500.0,
+        zeroing: None,
+        format: Format::Csv,
+    };
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--increment" => parsed.increment = number("increment", args.next())?,
+            "--max-range" => parsed.max_range = number("max range", args.next())?,
+            "--zero" => {
+                let zero = args.next().ok_or("missing zero")?;
+                let (sight_height, zero_distance) = zero
+                    .split_once(',')
+                    .ok_or("zero should be <sight_height_m>,<zero_distance_m>")?;
+                parsed.zeroing = Some(Zeroing::new(
+                    number("sight height", Some(sight_height.to_string()))?,
+                    number("zero distance", Some(zero_distance.to_string()))?,
+                ));
+            }
+            "--format" => {
+                parsed.format = match args.next().as_deref() {
+                    Some("csv") => Format::Csv,
+                    Some("json") => Format::Json,
+                    _ => return Err("format should be csv or json".to_string()),
+                }
+            }
+            _ => return Err(format!("unknown argument `{flag}`")),
+        }
+    }
+    Ok(parsed)
+}
+
+fn main() -> ExitCode {
+    let args = match parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(card) = args.config.range_card(
+        args.mass,
+        args.muzzle_speed,
+        args.increment,
+        args.max_range,
+        args.zeroing.as_ref(),
+    ) else {
+        eprintln!("the round can't reach the zero distance, so the sights can't be zeroed");
+        return ExitCode::FAILURE;
+    };
+    match args.format {
+        Format::Csv => print!("{}", card.to_csv()),
+        Format::Json => print!("{}", card.to_json()),
+    }
+    ExitCode::SUCCESS
+}
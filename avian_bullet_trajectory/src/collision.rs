@@ -0,0 +1,360 @@
+use avian3d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{BallisticMaterial, BallisticMaterials};
+
+/// Where a ray hit a collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayImpact {
+    /// The collider that was hit
+    pub entity: Entity,
+    /// Distance along the ray to the hit, zero if the ray started inside the collider
+    pub distance: f32,
+    /// Surface normal at the hit
+    pub normal: Vec3,
+}
+
+/// Collision geometry that bullets are simulated against
+///
+/// Implemented for avian's spatial queries by [`SpatialQueryWorld`], and for a list of planes
+/// and boxes by [`SimpleCollisionWorld`]. Implement it to run the simulation against another
+/// physics backend.
+pub trait CollisionWorld {
+    /// The closest collider hit by a ray within `max_distance`, skipping colliders that
+    /// `predicate` returns `false` for
+    ///
+    /// Colliders are solid, so a ray starting inside one hits it at distance zero.
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> Option<RayImpact>;
+
+    /// Whether a sphere of `radius` swept from `origin` for `max_distance` misses every collider
+    /// that `predicate` returns `true` for
+    ///
+    /// Lets the simulation skip the ray casts along a whole stretch of path at once. Answering
+    /// `false` is always safe, and is what the default does, so every step gets ray cast.
+    fn sweep_is_clear(
+        &self,
+        _origin: Vec3,
+        _direction: Dir3,
+        _max_distance: f32,
+        _radius: f32,
+        _predicate: &dyn Fn(Entity) -> bool,
+    ) -> bool {
+        false
+    }
+
+    /// The ballistic material of a collider, if bullets can ricochet off or pass through it
+    fn material(&self, _entity: Entity) -> Option<BallisticMaterial> {
+        None
+    }
+
+    /// Whether a collider is a sensor, which bullets can fly through
+    fn is_sensor(&self, _entity: Entity) -> bool {
+        false
+    }
+}
+
+/// Avian's spatial query pipeline, along with the lookup of which colliders are sensors
+///
+/// Derefs to [`SpatialQuery`], and implements [`BulletTrajectory`](crate::BulletTrajectory).
+#[derive(SystemParam, Deref)]
+pub struct BulletSpatialQuery<'w, 's> {
+    #[deref]
+    spatial_query: SpatialQuery<'w, 's>,
+    sensors: Query<'w, 's, (), With<Sensor>>,
+}
+
+/// Runs the simulation against avian's spatial query pipeline
+pub struct SpatialQueryWorld<'a, 'w, 's, 'mw, 'ms> {
+    spatial_query: &'a BulletSpatialQuery<'w, 's>,
+    filter: &'a SpatialQueryFilter,
+    materials: Option<&'a BallisticMaterials<'mw, 'ms>>,
+}
+
+impl<'a, 'w, 's, 'mw, 'ms> SpatialQueryWorld<'a, 'w, 's, 'mw, 'ms> {
+    /// Colliders that pass `filter`, which all stop bullets apart from sensors
+    pub fn new(
+        spatial_query: &'a BulletSpatialQuery<'w, 's>,
+        filter: &'a SpatialQueryFilter,
+    ) -> Self {
+        Self {
+            spatial_query,
+            filter,
+            materials: None,
+        }
+    }
+
+    /// Looks up the colliders' [`BallisticMaterial`]s
+    pub fn with_materials(mut self, materials: Option<&'a BallisticMaterials<'mw, 'ms>>) -> Self {
+        self.materials = materials;
+        self
+    }
+}
+
+impl CollisionWorld for SpatialQueryWorld<'_, '_, '_, '_, '_> {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> Option<RayImpact> {
+        self.spatial_query
+            .cast_ray_predicate(
+                origin,
+                direction,
+                max_distance,
+                true,
+                self.filter,
+                predicate,
+            )
+            .map(|hit| RayImpact {
+                entity: hit.entity,
+                distance: hit.distance,
+                normal: hit.normal,
+            })
+    }
+
+    fn sweep_is_clear(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        radius: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> bool {
+        self.spatial_query
+            .cast_shape_predicate(
+                &Collider::sphere(radius),
+                origin,
+                Quat::IDENTITY,
+                direction,
+                &ShapeCastConfig::from_max_distance(max_distance),
+                self.filter,
+                predicate,
+            )
+            .is_none()
+    }
+
+    fn material(&self, entity: Entity) -> Option<BallisticMaterial> {
+        self.materials?.get(entity).copied()
+    }
+
+    fn is_sensor(&self, entity: Entity) -> bool {
+        self.spatial_query.sensors.contains(entity)
+    }
+}
+
+/// Shape of a [`SimpleCollider`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimpleShape {
+    /// Everything behind a plane, like the ground
+    Plane {
+        /// Any point on the plane
+        point: Vec3,
+        /// Normal pointing out of the solid side
+        normal: Dir3,
+    },
+    /// An axis-aligned box
+    Aabb {
+        /// Corner with the smallest coordinates
+        min: Vec3,
+        /// Corner with the largest coordinates
+        max: Vec3,
+    },
+}
+
+impl SimpleShape {
+    /// The shape grown by `margin` on every side
+    fn inflated(&self, margin: f32) -> Self {
+        match *self {
+            SimpleShape::Plane { point, normal } => SimpleShape::Plane {
+                point: point + normal * margin,
+                normal,
+            },
+            SimpleShape::Aabb { min, max } => SimpleShape::Aabb {
+                min: min - margin,
+                max: max + margin,
+            },
+        }
+    }
+
+    fn cast_ray(&self, origin: Vec3, direction: Dir3) -> Option<(f32, Vec3)> {
+        match *self {
+            SimpleShape::Plane { point, normal } => {
+                let height = (origin - point).dot(*normal);
+                if height <= 0.0 {
+                    return Some((0.0, *normal));
+                }
+                let approach = direction.dot(*normal);
+                (approach < 0.0).then(|| (height / -approach, *normal))
+            }
+            SimpleShape::Aabb { min, max } => {
+                // Slab test, keeping track of the face the ray enters through
+                let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+                let mut normal = Vec3::ZERO;
+                for axis in 0..3 {
+                    let (start, step) = (origin[axis], direction.as_vec3()[axis]);
+                    if step == 0.0 {
+                        if start < min[axis] || start > max[axis] {
+                            return None;
+                        }
+                        continue;
+                    }
+                    let (mut entry, mut exit) =
+                        ((min[axis] - start) / step, (max[axis] - start) / step);
+                    if entry > exit {
+                        (entry, exit) = (exit, entry);
+                    }
+                    if entry > near {
+                        near = entry;
+                        normal = Vec3::ZERO;
+                        normal[axis] = -step.signum();
+                    }
+                    far = far.min(exit);
+                }
+                if near > far || far < 0.0 {
+                    return None;
+                }
+                // Inside the box, the way out is through the nearest face
+                if near < 0.0 {
+                    let mut nearest = f32::INFINITY;
+                    for axis in 0..3 {
+                        for (depth, sign) in [
+                            (origin[axis] - min[axis], -1.0),
+                            (max[axis] - origin[axis], 1.0),
+                        ] {
+                            if depth < nearest {
+                                nearest = depth;
+                                normal = Vec3::ZERO;
+                                normal[axis] = sign;
+                            }
+                        }
+                    }
+                    return Some((0.0, normal));
+                }
+                Some((near, normal))
+            }
+        }
+    }
+}
+
+/// A collider in a [`SimpleCollisionWorld`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimpleCollider {
+    /// Entity reported when the collider is hit
+    pub entity: Entity,
+    /// Geometry of the collider in world space
+    pub shape: SimpleShape,
+    /// Ballistic material, or `None` to stop every bullet
+    pub material: Option<BallisticMaterial>,
+    /// Whether bullets can fly through the collider
+    pub sensor: bool,
+}
+
+impl SimpleCollider {
+    /// A collider of the given shape that stops every bullet, reported as `entity` when hit
+    pub fn new(entity: Entity, shape: SimpleShape) -> Self {
+        Self {
+            entity,
+            shape,
+            material: None,
+            sensor: false,
+        }
+    }
+
+    /// Lets bullets ricochet off and pass through the collider as `material`
+    pub fn with_material(mut self, material: BallisticMaterial) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Makes the collider a sensor, which bullets fly through unless the config's
+    /// `skip_sensors` is off
+    pub fn with_sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+}
+
+/// A list of planes and boxes to simulate against without an ECS world, for tests, benchmarks
+/// and precomputed tables
+#[derive(Debug, Clone, Default)]
+pub struct SimpleCollisionWorld {
+    pub colliders: Vec<SimpleCollider>,
+}
+
+impl SimpleCollisionWorld {
+    /// A world of the given colliders. Where they overlap, a ray reports the nearest hit.
+    pub fn new(colliders: impl IntoIterator<Item = SimpleCollider>) -> Self {
+        Self {
+            colliders: colliders.into_iter().collect(),
+        }
+    }
+
+    fn collider(&self, entity: Entity) -> Option<&SimpleCollider> {
+        self.colliders
+            .iter()
+            .find(|collider| collider.entity == entity)
+    }
+}
+
+impl CollisionWorld for SimpleCollisionWorld {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> Option<RayImpact> {
+        self.colliders
+            .iter()
+            .filter(|collider| predicate(collider.entity))
+            .filter_map(|collider| {
+                let (distance, normal) = collider.shape.cast_ray(origin, direction)?;
+                (distance <= max_distance).then_some(RayImpact {
+                    entity: collider.entity,
+                    distance,
+                    normal,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    fn sweep_is_clear(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        radius: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> bool {
+        // Casting a ray against the shapes grown by the radius covers at least everything the
+        // sphere touches, only catching a little more around the box corners
+        !self
+            .colliders
+            .iter()
+            .filter(|collider| predicate(collider.entity))
+            .any(|collider| {
+                collider
+                    .shape
+                    .inflated(radius)
+                    .cast_ray(origin, direction)
+                    .is_some_and(|(distance, _)| distance <= max_distance)
+            })
+    }
+
+    fn material(&self, entity: Entity) -> Option<BallisticMaterial> {
+        self.collider(entity)?.material
+    }
+
+    fn is_sensor(&self, entity: Entity) -> bool {
+        self.collider(entity)
+            .is_some_and(|collider| collider.sensor)
+    }
+}
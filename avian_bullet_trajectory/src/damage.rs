@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+/// Turns the energy a bullet delivers into damage
+///
+/// Implemented for closures taking the energy in joules, so one-off models don't need a type.
+pub trait DamageModel: Send + Sync {
+    /// Damage dealt by a hit that delivers `energy` joules
+    fn damage(&self, energy: f32) -> f32;
+
+    /// Damage dealt to a collider, scaled by its [`Hitbox`] if it has one
+    fn hitbox_damage(&self, energy: f32, hitbox: Option<&Hitbox>) -> f32 {
+        self.damage(energy) * hitbox.map_or(1.0, |hitbox| hitbox.damage_multiplier)
+    }
+}
+
+impl<F: Fn(f32) -> f32 + Send + Sync> DamageModel for F {
+    fn damage(&self, energy: f32) -> f32 {
+        self(energy)
+    }
+}
+
+/// Damage proportional to the energy delivered, once it is enough to do any harm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnergyDamage {
+    /// Damage per joule delivered
+    pub damage_per_joule: f32,
+    /// Hits delivering less energy than this in joules do no damage
+    pub minimum_energy: f32,
+}
+
+impl DamageModel for EnergyDamage {
+    fn damage(&self, energy: f32) -> f32 {
+        if energy < self.minimum_energy {
+            0.0
+        } else {
+            energy * self.damage_per_joule
+        }
+    }
+}
+
+/// Scales the damage dealt to a collider, like a head or a limb
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Hitbox {
+    /// Multiplier applied to the damage of hits on this collider
+    pub damage_multiplier: f32,
+}
+
+impl Default for Hitbox {
+    fn default() -> Self {
+        Self {
+            damage_multiplier: 1.0,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+/// The state of the air at a point along the bullet's path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AirSample {
+    /// Wind velocity in m/s
+    pub wind: Vec3,
+    /// Air density in kg/m³
+    pub density: f32,
+    /// Speed of sound in m/s
+    pub speed_of_sound: f32,
+}
+
+impl Default for AirSample {
+    /// Still air at sea level
+    fn default() -> Self {
+        Self {
+            wind: Vec3::ZERO,
+            density: 1.225,
+            speed_of_sound: 340.3,
+        }
+    }
+}
+
+/// The air a bullet flies through, sampled along its path
+pub trait BallisticEnvironment: std::fmt::Debug + Send + Sync {
+    /// Samples the air at `position` (world space) and `time` (world time in seconds, the
+    /// config's `launch_time` plus the time since the shot)
+    fn sample(&self, position: Vec3, time: f32) -> AirSample;
+}
+
+/// The same wind and air everywhere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstantWind {
+    /// Wind velocity in m/s
+    pub wind: Vec3,
+    /// Air density in kg/m³
+    pub density: f32,
+    /// Speed of sound in m/s
+    pub speed_of_sound: f32,
+}
+
+impl Default for ConstantWind {
+    /// Still air at sea level
+    fn default() -> Self {
+        let air = AirSample::default();
+        Self {
+            wind: air.wind,
+            density: air.density,
+            speed_of_sound: air.speed_of_sound,
+        }
+    }
+}
+
+impl BallisticEnvironment for ConstantWind {
+    fn sample(&self, _position: Vec3, _time: f32) -> AirSample {
+        AirSample {
+            wind: self.wind,
+            density: self.density,
+            speed_of_sound: self.speed_of_sound,
+        }
+    }
+}
+
+/// International Standard Atmosphere: air density and speed of sound fall off with altitude
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StandardAtmosphere {
+    /// Altitude above sea level of world-space `y = 0` in meters
+    pub ground_altitude: f32,
+    /// Deviation from the standard sea level temperature of 15 °C, in kelvin
+    pub temperature_offset: f32,
+    /// Wind velocity in m/s
+    pub wind: Vec3,
+}
+
+impl StandardAtmosphere {
+    /// The air at the given altitude above sea level, ignoring wind
+    pub fn air_at(&self, altitude: f32) -> AirSample {
+        // Troposphere model: temperature drops linearly with altitude
+        const SEA_LEVEL_TEMPERATURE: f32 = 288.15;
+        const SEA_LEVEL_PRESSURE: f32 = 101_325.0;
+        const LAPSE_RATE: f32 = 0.0065;
+        const GAS_CONSTANT: f32 = 287.05;
+
+        let altitude = altitude.clamp(0.0, 11_000.0);
+        let pressure = SEA_LEVEL_PRESSURE
+            * (1.0 - LAPSE_RATE * altitude / SEA_LEVEL_TEMPERATURE).powf(5.255_88);
+        let temperature = SEA_LEVEL_TEMPERATURE - LAPSE_RATE * altitude + self.temperature_offset;
+
+        AirSample {
+            wind: Vec3::ZERO,
+            density: pressure / (GAS_CONSTANT * temperature),
+            speed_of_sound: (1.4 * GAS_CONSTANT * temperature).sqrt(),
+        }
+    }
+}
+
+impl BallisticEnvironment for StandardAtmosphere {
+    fn sample(&self, position: Vec3, _time: f32) -> AirSample {
+        AirSample {
+            wind: self.wind,
+            ..self.air_at(self.ground_altitude + position.y)
+        }
+    }
+}
+
+/// Adds seeded, gusting wind on top of another environment.
+///
+/// Gusts are carried along by the mean wind, so points downwind see the same gust a little later.
+/// They follow world time, so set the config's `launch_time` for each shot or every bullet will
+/// meet the same gusts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GustingWind<E = StandardAtmosphere> {
+    /// The environment providing the mean wind and the air
+    pub base: E,
+    /// Peak horizontal gust speed on top of the mean wind in m/s
+    pub gust_strength: f32,
+    /// Typical duration of a gust in seconds
+    pub gust_period: f32,
+    /// Seed for the gust noise. The same seed always produces the same gusts
+    pub seed: u64,
+}
+
+impl<E: BallisticEnvironment> BallisticEnvironment for GustingWind<E> {
+    fn sample(&self, position: Vec3, time: f32) -> AirSample {
+        let air = self.base.sample(position, time);
+        if self.gust_strength <= 0.0 || self.gust_period <= 0.0 {
+            return air;
+        }
+
+        // Shift the noise back in time the further downwind the sample is
+        let travel_time = air
+            .wind
+            .try_normalize()
+            .map_or(0.0, |direction| position.dot(direction) / air.wind.length());
+        let phase = (time - travel_time) / self.gust_period;
+        let gust = Vec3::new(
+            value_noise(self.seed, 0, phase),
+            // Vertical gusts are much weaker than horizontal ones
+            value_noise(self.seed, 1, phase) * 0.2,
+            value_noise(self.seed, 2, phase),
+        );
+
+        AirSample {
+            wind: air.wind + gust * self.gust_strength,
+            ..air
+        }
+    }
+}
+
+/// Smooth 1D value noise in `[-1, 1]`
+fn value_noise(seed: u64, channel: u64, x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let cell = cell as i64;
+    let a = hash(seed, channel, cell);
+    let b = hash(seed, channel, cell + 1);
+    a + (b - a) * t
+}
+
+/// SplitMix64-based hash of a lattice point to `[-1, 1]`
+fn hash(seed: u64, channel: u64, cell: i64) -> f32 {
+    let mut z = seed
+        ^ channel.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (cell as u64).wrapping_mul(0xD1B5_4A32_D192_ED03);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
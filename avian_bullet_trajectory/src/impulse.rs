@@ -0,0 +1,82 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{BulletTrajectoryResult, ProjectileHit};
+
+/// Pushes dynamic rigid bodies that bullets hit
+///
+/// Applies every [`BulletImpact`] to the body of the collider it targets, and turns
+/// [`ProjectileHit`]s into impacts. Static and kinematic bodies are left alone.
+pub struct ImpactImpulsePlugin;
+
+impl Plugin for ImpactImpulsePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(apply_bullet_impact)
+            .add_observer(impact_from_projectile_hit);
+    }
+}
+
+/// Triggered when a bullet hands some of its momentum to a collider
+#[derive(EntityEvent, Debug, Clone, Copy, PartialEq)]
+pub struct BulletImpact {
+    /// The collider that was hit
+    pub entity: Entity,
+    /// Where the bullet struck, in world space
+    pub point: Vec3,
+    /// Momentum handed to the collider in kg·m/s
+    pub impulse: Vec3,
+}
+
+impl BulletImpact {
+    /// Every impact along a simulated trajectory
+    ///
+    /// Each body gets the momentum the bullet lost on it: all of it where the bullet stopped, and
+    /// the difference between the velocities in and out where it passed through or ricocheted.
+    ///
+    /// # Parameters
+    /// - `result`: The simulated trajectory
+    /// - `mass`: Mass of the bullet in kg
+    pub fn from_trajectory(result: &BulletTrajectoryResult, mass: f32) -> Vec<Self> {
+        let penetrations = result.penetrations.iter().map(|penetration| Self {
+            entity: penetration.entity,
+            point: penetration.entry_point,
+            impulse: mass * (penetration.entry_velocity - penetration.exit_velocity),
+        });
+        let ricochets = result.ricochets.iter().map(|ricochet| Self {
+            entity: ricochet.entity,
+            point: ricochet.point,
+            impulse: mass * (ricochet.incoming_velocity - ricochet.outgoing_velocity),
+        });
+        let hit = result.hit_entity.map(|entity| Self {
+            entity,
+            point: result.hit_point,
+            impulse: result.impact_momentum,
+        });
+        penetrations.chain(ricochets).chain(hit).collect()
+    }
+}
+
+fn apply_bullet_impact(
+    impact: On<BulletImpact>,
+    colliders: Query<&ColliderOf>,
+    mut bodies: Query<(&RigidBody, Forces)>,
+) {
+    // Colliders can be children of the body they belong to
+    let body = colliders
+        .get(impact.entity)
+        .map_or(impact.entity, |collider| collider.body);
+    if let Ok((rigid_body, mut forces)) = bodies.get_mut(body)
+        && rigid_body.is_dynamic()
+    {
+        forces.apply_linear_impulse_at_point(impact.impulse, impact.point);
+    }
+}
+
+fn impact_from_projectile_hit(hit: On<ProjectileHit>, mut commands: Commands) {
+    // The projectile stops where it hits, so it hands over all of its momentum
+    commands.trigger(BulletImpact {
+        entity: hit.hit_entity,
+        point: hit.point,
+        impulse: hit.mass * hit.velocity,
+    });
+}
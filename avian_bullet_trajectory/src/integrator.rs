@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+
+/// Numerical integration scheme used to advance the bullet between collision checks
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Integrator {
+    /// Explicit (forward) Euler. Cheap, but drifts noticeably at long range
+    #[default]
+    Euler,
+    /// Semi-implicit (symplectic) Euler: the velocity is updated first and then used to move the bullet
+    SemiImplicitEuler,
+    /// Classic fourth-order Runge-Kutta with a fixed time step
+    Rk4,
+    /// Adaptive Dormand-Prince RK4(5). The step grows and shrinks to keep the
+    /// estimated error of each step below `tolerance`
+    Rk45 {
+        /// Maximum accepted error per step in meters
+        tolerance: f32,
+        /// Smallest step the integrator is allowed to take in seconds
+        min_step: f32,
+        /// Largest step the integrator is allowed to take in seconds
+        max_step: f32,
+    },
+}
+
+impl Integrator {
+    /// Adaptive RK45 with step limits suited to small-arms ballistics
+    pub fn adaptive(tolerance: f32) -> Self {
+        Self::Rk45 {
+            tolerance,
+            min_step: 0.00001,
+            max_step: 0.1,
+        }
+    }
+}
+
+/// Outcome of a single integration step
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Step {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// The time actually covered by this step
+    pub dt: f32,
+    /// The step size to attempt next (only differs from `dt` for adaptive integrators)
+    pub next_dt: f32,
+}
+
+// Dormand-Prince 5(4) Butcher tableau
+const DP_C: [f32; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DP_A: [[f32; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+/// Fifth-order solution weights
+const DP_B: [f32; 7] = [
+    35.0 / 384.0,
+    0.0,
+    500.0 / 1113.0,
+    125.0 / 192.0,
+    -2187.0 / 6784.0,
+    11.0 / 84.0,
+    0.0,
+];
+/// Difference between the fifth- and fourth-order weights, used for the error estimate
+const DP_E: [f32; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+impl Integrator {
+    /// Advances the state by one step.
+    ///
+    /// `acceleration` is evaluated as `acceleration(time, position, velocity)`.
+    pub(crate) fn step(
+        &self,
+        time: f32,
+        position: Vec3,
+        velocity: Vec3,
+        dt: f32,
+        acceleration: impl Fn(f32, Vec3, Vec3) -> Vec3,
+    ) -> Step {
+        match *self {
+            Integrator::Euler => Step {
+                position: position + velocity * dt,
+                velocity: velocity + acceleration(time, position, velocity) * dt,
+                dt,
+                next_dt: dt,
+            },
+            Integrator::SemiImplicitEuler => {
+                let velocity = velocity + acceleration(time, position, velocity) * dt;
+                Step {
+                    position: position + velocity * dt,
+                    velocity,
+                    dt,
+                    next_dt: dt,
+                }
+            }
+            Integrator::Rk4 => {
+                let half = dt * 0.5;
+                let (k1p, k1v) = (velocity, acceleration(time, position, velocity));
+                let k2p = velocity + k1v * half;
+                let k2v = acceleration(time + half, position + k1p * half, k2p);
+                let k3p = velocity + k2v * half;
+                let k3v = acceleration(time + half, position + k2p * half, k3p);
+                let k4p = velocity + k3v * dt;
+                let k4v = acceleration(time + dt, position + k3p * dt, k4p);
+                Step {
+                    position: position + (k1p + 2.0 * k2p + 2.0 * k3p + k4p) * (dt / 6.0),
+                    velocity: velocity + (k1v + 2.0 * k2v + 2.0 * k3v + k4v) * (dt / 6.0),
+                    dt,
+                    next_dt: dt,
+                }
+            }
+            Integrator::Rk45 {
+                tolerance,
+                min_step,
+                max_step,
+            } => {
+                let mut dt = dt.clamp(min_step, max_step);
+                loop {
+                    let mut k = [(Vec3::ZERO, Vec3::ZERO); 7];
+                    for (stage, row) in DP_A.iter().enumerate() {
+                        let mut p = position;
+                        let mut v = velocity;
+                        for (j, &(kp, kv)) in k.iter().enumerate().take(stage) {
+                            p += kp * row[j] * dt;
+                            v += kv * row[j] * dt;
+                        }
+                        k[stage] = (v, acceleration(time + DP_C[stage] * dt, p, v));
+                    }
+
+                    let (mut new_position, mut new_velocity) = (position, velocity);
+                    let (mut position_error, mut velocity_error) = (Vec3::ZERO, Vec3::ZERO);
+                    for (i, &(kp, kv)) in k.iter().enumerate() {
+                        new_position += kp * DP_B[i] * dt;
+                        new_velocity += kv * DP_B[i] * dt;
+                        position_error += kp * DP_E[i] * dt;
+                        velocity_error += kv * DP_E[i] * dt;
+                    }
+
+                    // Velocity error is scaled by the step to express it as a distance as well
+                    let error = position_error.length().max(velocity_error.length() * dt);
+                    let scale = if error > 0.0 {
+                        0.9 * (tolerance / error).powf(0.2)
+                    } else {
+                        5.0
+                    };
+
+                    if error <= tolerance || dt <= min_step {
+                        return Step {
+                            position: new_position,
+                            velocity: new_velocity,
+                            dt,
+                            next_dt: (dt * scale.min(5.0)).clamp(min_step, max_step),
+                        };
+                    }
+
+                    dt = (dt * scale.max(0.2)).max(min_step);
+                }
+            }
+        }
+    }
+}
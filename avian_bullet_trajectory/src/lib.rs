@@ -1,11 +1,55 @@
+use std::sync::Arc;
+
 use avian3d::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    tasks::{ComputeTaskPool, TaskPool},
+};
+
+mod collision;
+mod damage;
+mod drag;
+mod effects;
+mod environment;
+mod impulse;
+mod integrator;
+mod material;
+mod projectile;
+mod range_card;
+mod recording;
+mod simulation;
+mod solver;
+mod spread;
+mod zeroing;
+
+pub use collision::{
+    BulletSpatialQuery, CollisionWorld, RayImpact, SimpleCollider, SimpleCollisionWorld,
+    SimpleShape, SpatialQueryWorld,
+};
+pub use damage::{DamageModel, EnergyDamage, Hitbox};
+pub use drag::DragModel;
+pub use effects::{Coriolis, SpinDrift};
+pub use environment::{
+    AirSample, BallisticEnvironment, ConstantWind, GustingWind, StandardAtmosphere,
+};
+pub use impulse::{BulletImpact, ImpactImpulsePlugin};
+pub use integrator::Integrator;
+pub use material::{BallisticMaterial, BallisticMaterials, ImpactEffect};
+pub use projectile::{Projectile, ProjectileHit, ProjectilePlugin};
+pub use range_card::{RangeCard, RangeCardRow};
+pub use recording::TrajectoryRecording;
+pub use simulation::{simulate_simple_trajectory, simulate_trajectory};
+pub use solver::{InterceptSolution, LaunchSolution, LaunchSolutions};
+pub use spread::SpreadPattern;
+pub use zeroing::Zeroing;
 
 /// Result of a bullet trajectory simulation
 #[derive(Debug, Clone)]
 pub struct BulletTrajectoryResult {
     /// The entity that was hit, if any
     pub hit_entity: Option<Entity>,
+    /// The material of the entity that was hit, if it has one and materials were looked up
+    pub hit_material: Option<BallisticMaterial>,
     /// The point where the bullet hit or the final position
     pub hit_point: Vec3,
     /// The distance traveled by the bullet
@@ -16,6 +60,48 @@ pub struct BulletTrajectoryResult {
     pub trajectory_points: Vec<Vec3>,
     /// The velocity at impact
     pub impact_velocity: Vec3,
+    /// Kinetic energy at impact in joules (zero for simple trajectories, which have no mass)
+    pub impact_energy: f32,
+    /// Momentum at impact in kg·m/s (zero for simple trajectories, which have no mass)
+    pub impact_momentum: Vec3,
+    /// Every collider the bullet passed through before stopping, in order
+    pub penetrations: Vec<Penetration>,
+    /// Every surface the bullet bounced off before stopping, in order
+    pub ricochets: Vec<Ricochet>,
+}
+
+/// A collider the bullet passed through
+#[derive(Debug, Clone, Copy)]
+pub struct Penetration {
+    /// The entity that was passed through
+    pub entity: Entity,
+    /// The material of the entity
+    pub material: BallisticMaterial,
+    /// The point where the bullet entered the collider
+    pub entry_point: Vec3,
+    /// The velocity of the bullet as it entered the collider
+    pub entry_velocity: Vec3,
+    /// The point where the bullet left the collider
+    pub exit_point: Vec3,
+    /// The velocity of the bullet as it left the collider
+    pub exit_velocity: Vec3,
+}
+
+/// A surface the bullet bounced off
+#[derive(Debug, Clone, Copy)]
+pub struct Ricochet {
+    /// The entity that was bounced off
+    pub entity: Entity,
+    /// The material of the entity
+    pub material: BallisticMaterial,
+    /// The point of impact
+    pub point: Vec3,
+    /// The surface normal at the point of impact
+    pub normal: Vec3,
+    /// The velocity of the bullet before the bounce
+    pub incoming_velocity: Vec3,
+    /// The velocity of the bullet after the bounce
+    pub outgoing_velocity: Vec3,
 }
 
 /// Configuration for bullet physics simulation
@@ -23,40 +109,72 @@ pub struct BulletTrajectoryResult {
 pub struct BulletPhysicsConfig {
     /// Gravity acceleration (default: -9.81 m/s² on Y axis)
     pub gravity: Vec3,
-    /// Air resistance coefficient (default: 0.47 for a sphere)
-    pub drag_coefficient: f32,
-    /// Cross-sectional area of the bullet in m² (default: 0.00002 for a 5mm bullet)
+    /// Air resistance model (default: constant 0.47 for a sphere)
+    pub drag_model: DragModel,
+    /// Cross-sectional area of the bullet in m², used by the constant drag model (default: 0.00002 for a 5mm bullet)
     pub cross_section_area: f32,
-    /// Air density in kg/m³ (default: 1.225 at sea level)
-    pub air_density: f32,
+    /// Wind, air density and speed of sound along the path (default: still air at sea level)
+    pub environment: Arc<dyn BallisticEnvironment>,
+    /// World time of the shot in seconds, which the environment is sampled from, so shots fired
+    /// at different times meet different gusts (default: 0.0)
+    pub launch_time: f32,
     /// Maximum simulation time in seconds (default: 10.0)
     pub max_time: f32,
-    /// Time step for simulation in seconds (default: 0.001)
+    /// Time step for simulation in seconds, or the initial step for adaptive integrators (default: 0.001)
     pub time_step: f32,
+    /// Integration scheme used to advance the bullet (default: explicit Euler)
+    pub integrator: Integrator,
     /// Maximum distance to simulate (default: 1000.0)
     pub max_distance: f32,
+    /// Thickest collider a bullet can pass through in meters (default: 1.0)
+    pub max_penetration_depth: f32,
+    /// Maximum number of times a bullet can ricochet (default: 3)
+    pub max_ricochets: usize,
+    /// Which points of the path end up in `trajectory_points` (default: every step)
+    pub recording: TrajectoryRecording,
+    /// Gyroscopic spin drift of the bullet (default: off)
+    pub spin_drift: Option<SpinDrift>,
+    /// Coriolis and Eötvös deflection from the earth's rotation (default: off)
+    pub coriolis: Option<Coriolis>,
+    /// Whether bullets fly through sensor colliders, like trigger volumes (default: true).
+    /// Sensors are only known when materials are looked up, or for projectiles
+    pub skip_sensors: bool,
+    /// How many steps to look ahead with a single sweep, skipping their ray casts when nothing
+    /// is near the path (default: 64, 0 ray casts every step)
+    pub broad_phase_steps: usize,
 }
 
 impl Default for BulletPhysicsConfig {
     fn default() -> Self {
         Self {
             gravity: Vec3::new(0.0, -9.81, 0.0),
-            drag_coefficient: 0.47,
+            drag_model: DragModel::default(),
             cross_section_area: 0.00002, // ~5mm bullet
-            air_density: 1.225,
+            environment: Arc::new(ConstantWind::default()),
+            launch_time: 0.0,
             max_time: 30.0,
             time_step: 0.001,
+            integrator: Integrator::default(),
             max_distance: 10000.0,
+            max_penetration_depth: 1.0,
+            max_ricochets: 3,
+            recording: TrajectoryRecording::default(),
+            spin_drift: None,
+            coriolis: None,
+            skip_sensors: true,
+            broad_phase_steps: 64,
         }
     }
 }
 
-#[allow(dead_code)]
 impl BulletPhysicsConfig {
     /// 9mm Parabellum configuration
     pub fn caliber_9mm() -> Self {
         Self {
-            drag_coefficient: 0.295,       // Typical for FMJ bullets
+            // 115gr FMJ
+            drag_model: DragModel::G1 {
+                ballistic_coefficient: 0.130,
+            },
             cross_section_area: 0.0000636, // 9mm diameter
             ..Default::default()
         }
@@ -65,7 +183,10 @@ impl BulletPhysicsConfig {
     /// 5.56x45mm NATO / .223 Remington configuration
     pub fn caliber_556() -> Self {
         Self {
-            drag_coefficient: 0.275,       // Typical for rifle bullets
+            // M855 62gr FMJ-BT
+            drag_model: DragModel::G7 {
+                ballistic_coefficient: 0.151,
+            },
             cross_section_area: 0.0000243, // 5.56mm diameter
             ..Default::default()
         }
@@ -74,7 +195,10 @@ impl BulletPhysicsConfig {
     /// 7.62x51mm NATO / .308 Winchester configuration
     pub fn caliber_762() -> Self {
         Self {
-            drag_coefficient: 0.290,
+            // M80 147gr FMJ-BT
+            drag_model: DragModel::G7 {
+                ballistic_coefficient: 0.200,
+            },
             cross_section_area: 0.0000456, // 7.62mm diameter
             ..Default::default()
         }
@@ -83,14 +207,61 @@ impl BulletPhysicsConfig {
     /// .50 BMG (12.7x99mm) configuration
     pub fn caliber_50bmg() -> Self {
         Self {
-            drag_coefficient: 0.320,
+            // M33 661gr FMJ-BT
+            drag_model: DragModel::G1 {
+                ballistic_coefficient: 0.620,
+            },
             cross_section_area: 0.0001267, // 12.7mm diameter
             ..Default::default()
         }
     }
+
+    /// 12 gauge 00 buckshot configuration, for a single pellet
+    pub fn buckshot_00() -> Self {
+        Self {
+            // Round lead pellet
+            drag_model: DragModel::Constant {
+                drag_coefficient: 0.47,
+            },
+            cross_section_area: 0.0000552, // 8.38mm diameter
+            ..Default::default()
+        }
+    }
+
+    /// Total acceleration (gravity, drag and any secondary effects) on a bullet of the given mass
+    pub fn acceleration(&self, time: f32, position: Vec3, velocity: Vec3, mass: f32) -> Vec3 {
+        let drag = self.drag_acceleration(position, time, velocity, mass);
+        let mut acceleration = self.gravity + drag;
+        if let Some(spin_drift) = &self.spin_drift {
+            let up = (-self.gravity).try_normalize().unwrap_or(Vec3::Y);
+            acceleration += spin_drift.acceleration(time, velocity, up, drag);
+        }
+        if let Some(coriolis) = &self.coriolis {
+            acceleration += coriolis.acceleration(velocity);
+        }
+        acceleration
+    }
+
+    /// Drag acceleration on a bullet of the given mass moving at `velocity`.
+    ///
+    /// The environment is sampled at `position` and `time` after the launch time, and drag acts
+    /// on the velocity relative to the wind.
+    pub fn drag_acceleration(&self, position: Vec3, time: f32, velocity: Vec3, mass: f32) -> Vec3 {
+        let air = self.environment.sample(position, self.launch_time + time);
+        let relative_velocity = velocity - air.wind;
+        let speed = relative_velocity.length();
+        -self.drag_model.deceleration_factor(
+            speed,
+            air.density,
+            air.speed_of_sound,
+            mass,
+            self.cross_section_area,
+        ) * speed
+            * relative_velocity
+    }
 }
 
-/// Trait extension for SpatialQuery to simulate bullet trajectories
+/// Trait extension for [`BulletSpatialQuery`] to simulate bullet trajectories
 pub trait BulletTrajectory {
     /// Simulates a bullet trajectory with physics until it hits something
     ///
@@ -112,6 +283,81 @@ pub trait BulletTrajectory {
         filter: &SpatialQueryFilter,
     ) -> BulletTrajectoryResult;
 
+    /// Simulates a bullet trajectory that can ricochet off and pass through colliders with a
+    /// [`BallisticMaterial`]
+    ///
+    /// When the bullet hits a material at a shallower angle than its `ricochet_angle`, it is
+    /// deflected off the surface, losing velocity according to the material's restitution and
+    /// friction. Otherwise the far side of the collider is found by casting back towards the
+    /// entry point. The bullet loses energy according to the thickness it passed through and
+    /// carries on from the exit point if any is left.
+    ///
+    /// # Parameters
+    /// - `start_position`: Initial position of the bullet
+    /// - `initial_velocity`: Initial velocity vector of the bullet (m/s)
+    /// - `mass`: Mass of the bullet in kg
+    /// - `config`: Optional physics configuration (uses defaults if None)
+    /// - `filter`: Spatial query filter for collision detection
+    /// - `materials`: Lookup for the materials of hit colliders
+    ///
+    /// # Returns
+    /// A `BulletTrajectoryResult` containing hit information, trajectory data and every ricochet
+    /// and penetration along the way
+    fn simulate_bullet_with_materials(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: &BallisticMaterials,
+    ) -> BulletTrajectoryResult;
+
+    /// Simulates every pellet of a multi-projectile shot, like a shotgun blast
+    ///
+    /// The pellets are spread around `initial_velocity` according to `spread`. With `materials`,
+    /// each pellet can ricochet and penetrate as in
+    /// [`BulletTrajectory::simulate_bullet_with_materials`].
+    ///
+    /// # Parameters
+    /// - `start_position`: Initial position of the pellets
+    /// - `initial_velocity`: Velocity of the shot along the aim direction (m/s)
+    /// - `mass`: Mass of a single pellet in kg
+    /// - `spread`: How the pellets are scattered
+    /// - `config`: Optional physics configuration (uses defaults if None)
+    /// - `filter`: Spatial query filter for collision detection
+    /// - `materials`: Optional lookup for the materials of hit colliders
+    ///
+    /// # Returns
+    /// One `BulletTrajectoryResult` per pellet
+    #[allow(clippy::too_many_arguments)]
+    fn simulate_spread(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        spread: &SpreadPattern,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: Option<&BallisticMaterials>,
+    ) -> Vec<BulletTrajectoryResult>;
+
+    /// Like [`BulletTrajectory::simulate_spread`], but simulates the pellets in parallel on the
+    /// [`ComputeTaskPool`]
+    ///
+    /// The results are in the same order, and identical to those of `simulate_spread`.
+    #[allow(clippy::too_many_arguments)]
+    fn par_simulate_spread(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        spread: &SpreadPattern,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: Option<&BallisticMaterials>,
+    ) -> Vec<BulletTrajectoryResult>;
+
     /// Simulates a simple ballistic trajectory without air resistance
     ///
     /// # Parameters
@@ -121,17 +367,36 @@ pub trait BulletTrajectory {
     /// - `filter`: Spatial query filter for collision detection
     ///
     /// # Returns
-    /// A `BulletTrajectoryResult` containing hit information and trajectory data
+    /// A `BulletTrajectoryResult` containing hit information and every step of the path
     fn simulate_simple_trajectory(
         &self,
         start_position: Vec3,
         initial_velocity: Vec3,
         gravity: Option<Vec3>,
         filter: &SpatialQueryFilter,
+    ) -> BulletTrajectoryResult {
+        self.simulate_simple_trajectory_with_recording(
+            start_position,
+            initial_velocity,
+            gravity,
+            TrajectoryRecording::default(),
+            filter,
+        )
+    }
+
+    /// Like [`BulletTrajectory::simulate_simple_trajectory`], keeping only the points of the
+    /// path picked by `recording`
+    fn simulate_simple_trajectory_with_recording(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        gravity: Option<Vec3>,
+        recording: TrajectoryRecording,
+        filter: &SpatialQueryFilter,
     ) -> BulletTrajectoryResult;
 }
 
-impl BulletTrajectory for SpatialQuery<'_, '_> {
+impl BulletTrajectory for BulletSpatialQuery<'_, '_> {
     fn simulate_bullet_trajectory(
         &self,
         start_position: Vec3,
@@ -140,142 +405,88 @@ impl BulletTrajectory for SpatialQuery<'_, '_> {
         config: Option<BulletPhysicsConfig>,
         filter: &SpatialQueryFilter,
     ) -> BulletTrajectoryResult {
-        let config = config.unwrap_or_default();
-
-        let mut position = start_position;
-        let mut velocity = initial_velocity;
-        let mut trajectory_points = vec![position];
-        let mut time = 0.0;
-        let mut total_distance = 0.0;
-
-        // Pre-calculate drag constant for efficiency
-        let drag_constant =
-            0.5 * config.air_density * config.drag_coefficient * config.cross_section_area;
-
-        while time < config.max_time && total_distance < config.max_distance {
-            // Calculate drag force: F_drag = 0.5 * ρ * C_d * A * v²
-            let velocity_magnitude = velocity.length();
-            let drag_force = if velocity_magnitude > 0.0 {
-                -drag_constant * velocity_magnitude * velocity / mass
-            } else {
-                Vec3::ZERO
-            };
-
-            // Total acceleration = gravity + drag/mass
-            let acceleration = config.gravity + drag_force;
-
-            // Update velocity and position using Euler integration
-            let new_velocity = velocity + acceleration * config.time_step;
-            let new_position = position + velocity * config.time_step;
+        simulate_trajectory(
+            &SpatialQueryWorld::new(self, filter),
+            start_position,
+            initial_velocity,
+            mass,
+            &config.unwrap_or_default(),
+        )
+    }
 
-            // Check for collision along the path segment
-            let segment_vector = new_position - position;
-            let segment_distance = segment_vector.length();
+    fn simulate_bullet_with_materials(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: &BallisticMaterials,
+    ) -> BulletTrajectoryResult {
+        simulate_trajectory(
+            &SpatialQueryWorld::new(self, filter).with_materials(Some(materials)),
+            start_position,
+            initial_velocity,
+            mass,
+            &config.unwrap_or_default(),
+        )
+    }
 
-            // Convert Vec3 to Dir3 for the cast_ray function
-            if let Ok(segment_direction) = Dir3::try_from(segment_vector)
-                && let Some(hit) =
-                    self.cast_ray(position, segment_direction, segment_distance, true, filter)
-            {
-                // We hit something!
-                let hit_point = position + segment_direction * hit.distance;
-                trajectory_points.push(hit_point);
+    fn simulate_spread(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        spread: &SpreadPattern,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: Option<&BallisticMaterials>,
+    ) -> Vec<BulletTrajectoryResult> {
+        let config = config.unwrap_or_default();
+        let world = SpatialQueryWorld::new(self, filter).with_materials(materials);
+        spread
+            .velocities(initial_velocity)
+            .into_iter()
+            .map(|velocity| simulate_trajectory(&world, start_position, velocity, mass, &config))
+            .collect()
+    }
 
-                return BulletTrajectoryResult {
-                    hit_entity: Some(hit.entity),
-                    hit_point,
-                    distance: total_distance + hit.distance,
-                    time_of_flight: time + (hit.distance / velocity_magnitude) * config.time_step,
-                    trajectory_points,
-                    impact_velocity: velocity,
-                };
+    fn par_simulate_spread(
+        &self,
+        start_position: Vec3,
+        initial_velocity: Vec3,
+        mass: f32,
+        spread: &SpreadPattern,
+        config: Option<BulletPhysicsConfig>,
+        filter: &SpatialQueryFilter,
+        materials: Option<&BallisticMaterials>,
+    ) -> Vec<BulletTrajectoryResult> {
+        let config = &config.unwrap_or_default();
+        let world = &SpatialQueryWorld::new(self, filter).with_materials(materials);
+        let velocities = spread.velocities(initial_velocity);
+        ComputeTaskPool::get_or_init(TaskPool::new).scope(|scope| {
+            for velocity in velocities {
+                scope.spawn(async move {
+                    simulate_trajectory(world, start_position, velocity, mass, config)
+                });
             }
-
-            // Update for next iteration
-            position = new_position;
-            velocity = new_velocity;
-            time += config.time_step;
-            total_distance += segment_distance;
-
-            trajectory_points.push(position);
-        }
-
-        // No hit found within simulation limits
-        BulletTrajectoryResult {
-            hit_entity: None,
-            hit_point: position,
-            distance: total_distance,
-            time_of_flight: time,
-            trajectory_points,
-            impact_velocity: velocity,
-        }
+        })
     }
 
-    fn simulate_simple_trajectory(
+    fn simulate_simple_trajectory_with_recording(
         &self,
         start_position: Vec3,
         initial_velocity: Vec3,
         gravity: Option<Vec3>,
+        recording: TrajectoryRecording,
         filter: &SpatialQueryFilter,
     ) -> BulletTrajectoryResult {
-        let gravity = gravity.unwrap_or(Vec3::new(0.0, -9.81, 0.0));
-        let time_step = 0.01; // 10ms steps for simple simulation
-        let max_time = 30.0;
-        let max_distance = 10000.0;
-
-        let mut position = start_position;
-        let mut velocity = initial_velocity;
-        let mut trajectory_points = vec![position];
-        let mut time = 0.0;
-        let mut total_distance = 0.0;
-
-        while time < max_time && total_distance < max_distance {
-            // Simple physics: v = v0 + at, s = s0 + vt
-            let new_velocity = velocity + gravity * time_step;
-            let new_position = position + velocity * time_step;
-
-            // Check for collision
-            let segment_vector = new_position - position;
-            let segment_distance = segment_vector.length();
-
-            if segment_distance > 0.0 {
-                // Convert Vec3 to Dir3 for the cast_ray function
-                if let Ok(segment_direction) = Dir3::try_from(segment_vector)
-                    && let Some(hit) =
-                        self.cast_ray(position, segment_direction, segment_distance, true, filter)
-                {
-                    let hit_point = position + segment_direction * hit.distance;
-                    trajectory_points.push(hit_point);
-
-                    return BulletTrajectoryResult {
-                        hit_entity: Some(hit.entity),
-                        hit_point,
-                        distance: total_distance + hit.distance,
-                        time_of_flight: time + (hit.distance / velocity.length()) * time_step,
-                        trajectory_points,
-                        impact_velocity: velocity,
-                    };
-                }
-            }
-
-            position = new_position;
-            velocity = new_velocity;
-            time += time_step;
-            total_distance += segment_distance;
-
-            // Store trajectory points periodically
-            if (trajectory_points.len() as f32 * 0.1) <= time {
-                trajectory_points.push(position);
-            }
-        }
-
-        BulletTrajectoryResult {
-            hit_entity: None,
-            hit_point: position,
-            distance: total_distance,
-            time_of_flight: time,
-            trajectory_points,
-            impact_velocity: velocity,
-        }
+        simulation::simulate_simple_trajectory(
+            &SpatialQueryWorld::new(self, filter),
+            start_position,
+            initial_velocity,
+            gravity.unwrap_or(Vec3::new(0.0, -9.81, 0.0)),
+            recording,
+        )
     }
 }
@@ -0,0 +1,125 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// Ballistic properties of a collider.
+///
+/// Colliders without this component stop every bullet that hits them.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct BallisticMaterial {
+    /// Kinetic energy a bullet loses per meter of material it passes through (J/m)
+    pub penetration_resistance: f32,
+    /// Bullets hitting the surface at a shallower angle than this ricochet (radians from the surface)
+    pub ricochet_angle: f32,
+    /// Fraction of the velocity along the surface normal that is kept on a ricochet
+    pub restitution: f32,
+    /// Fraction of the velocity along the surface that is lost on a ricochet
+    pub friction: f32,
+    /// Which effect to play where bullets hit the surface
+    pub impact_effect: ImpactEffect,
+}
+
+/// What a bullet impact on a surface looks like, for picking particles and sounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+pub enum ImpactEffect {
+    /// A puff of dust and grit
+    #[default]
+    Dust,
+    /// Torn paper or card
+    Paper,
+    /// Wood chips and splinters
+    Splinters,
+    /// Broken glass
+    Shards,
+    /// Sparks off metal
+    Sparks,
+    /// A game-defined effect
+    Custom(u32),
+}
+
+impl Default for BallisticMaterial {
+    fn default() -> Self {
+        Self {
+            penetration_resistance: f32::INFINITY,
+            ricochet_angle: 0.0,
+            restitution: 0.0,
+            friction: 0.0,
+            impact_effect: ImpactEffect::Dust,
+        }
+    }
+}
+
+impl BallisticMaterial {
+    /// Cardboard and paper target backers
+    pub fn cardboard() -> Self {
+        Self {
+            penetration_resistance: 1_000.0,
+            impact_effect: ImpactEffect::Paper,
+            ..Default::default()
+        }
+    }
+
+    /// Gypsum drywall
+    pub fn drywall() -> Self {
+        Self {
+            penetration_resistance: 2_000.0,
+            ..Default::default()
+        }
+    }
+
+    /// Glass panes
+    pub fn glass() -> Self {
+        Self {
+            penetration_resistance: 1_500.0,
+            ricochet_angle: 0.09, // ~5°
+            restitution: 0.2,
+            friction: 0.3,
+            impact_effect: ImpactEffect::Shards,
+        }
+    }
+
+    /// Plywood and other thin wooden boards
+    pub fn plywood() -> Self {
+        Self {
+            penetration_resistance: 6_000.0,
+            ricochet_angle: 0.12, // ~7°
+            restitution: 0.2,
+            friction: 0.4,
+            impact_effect: ImpactEffect::Splinters,
+        }
+    }
+
+    /// Poured concrete
+    pub fn concrete() -> Self {
+        Self {
+            penetration_resistance: 60_000.0,
+            ricochet_angle: 0.26, // ~15°
+            restitution: 0.2,
+            friction: 0.3,
+            impact_effect: ImpactEffect::Dust,
+        }
+    }
+
+    /// Mild steel plate
+    pub fn steel() -> Self {
+        Self {
+            penetration_resistance: 300_000.0,
+            ricochet_angle: 0.52, // ~30°
+            restitution: 0.3,
+            friction: 0.15,
+            impact_effect: ImpactEffect::Sparks,
+        }
+    }
+}
+
+/// Looks up the [`BallisticMaterial`] of colliders hit during a simulation
+#[derive(SystemParam)]
+pub struct BallisticMaterials<'w, 's> {
+    materials: Query<'w, 's, &'static BallisticMaterial>,
+}
+
+impl BallisticMaterials<'_, '_> {
+    /// The material of the given collider, if it has one
+    pub fn get(&self, entity: Entity) -> Option<&BallisticMaterial> {
+        self.materials.get(entity).ok()
+    }
+}
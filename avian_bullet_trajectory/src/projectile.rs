@@ -0,0 +1,164 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::BulletPhysicsConfig;
+
+/// Advances [`Projectile`]s every `FixedUpdate` tick
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, advance_projectiles);
+    }
+}
+
+/// A bullet in flight.
+///
+/// Instead of simulating the whole trajectory up front, the bullet is advanced one fixed tick at
+/// a time and only that tick's path is checked for collisions, so it interacts correctly with
+/// targets that move while it is in the air. The entity's [`Transform`] is the bullet's position.
+///
+/// When it hits something, a [`ProjectileHit`] is triggered and the entity is despawned. It is
+/// also despawned once it exceeds the config's `max_time` or `max_distance`.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct Projectile {
+    /// Current velocity in m/s
+    pub velocity: Vec3,
+    /// Mass of the bullet in kg
+    pub mass: f32,
+    /// Physics configuration
+    pub config: BulletPhysicsConfig,
+    /// Spatial query filter for collision detection
+    pub filter: SpatialQueryFilter,
+    /// Time since the bullet was fired in seconds
+    pub time: f32,
+    /// Distance traveled so far in meters
+    pub distance: f32,
+    /// Step size carried over between ticks
+    step: f32,
+}
+
+impl Projectile {
+    /// A bullet just leaving the muzzle with `velocity` in m/s and `mass` in kg. Spawn it with a
+    /// [`Transform`] at the muzzle. It hits every collider until given a filter.
+    pub fn new(velocity: Vec3, mass: f32, config: BulletPhysicsConfig) -> Self {
+        Self {
+            velocity,
+            mass,
+            step: config.time_step,
+            config,
+            filter: SpatialQueryFilter::default(),
+            time: 0.0,
+            distance: 0.0,
+        }
+    }
+
+    /// Only hit colliders that pass `filter`, for example to skip the shooter's own collider
+    pub fn with_filter(mut self, filter: SpatialQueryFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Triggered when a [`Projectile`] hits something
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ProjectileHit {
+    /// The projectile, which is despawned after this event
+    pub entity: Entity,
+    /// The entity that was hit
+    pub hit_entity: Entity,
+    /// The point of impact
+    pub point: Vec3,
+    /// The surface normal at the point of impact
+    pub normal: Vec3,
+    /// The velocity at impact
+    pub velocity: Vec3,
+    /// Mass of the bullet in kg
+    pub mass: f32,
+    /// Time since the bullet was fired in seconds
+    pub time_of_flight: f32,
+    /// Distance traveled in meters
+    pub distance: f32,
+}
+
+fn advance_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    sensors: Query<(), With<Sensor>>,
+    mut projectiles: Query<(Entity, &mut Projectile, &mut Transform)>,
+) {
+    let tick = time.delta_secs();
+    'projectiles: for (entity, mut projectile, mut transform) in &mut projectiles {
+        let Projectile {
+            velocity,
+            mass,
+            config,
+            filter,
+            time,
+            distance,
+            step: dt,
+        } = &mut *projectile;
+        let acceleration = |time: f32, position: Vec3, velocity: Vec3| {
+            config.acceleration(time, position, velocity, *mass)
+        };
+        let solid = |entity: Entity| !config.skip_sensors || !sensors.contains(entity);
+
+        let tick_end = *time + tick;
+        let mut position = transform.translation;
+        while *time < tick_end {
+            if *time >= config.max_time || *distance >= config.max_distance {
+                commands.entity(entity).despawn();
+                continue 'projectiles;
+            }
+
+            // Don't step past the end of the tick
+            let remaining = tick_end - *time;
+            let step =
+                config
+                    .integrator
+                    .step(*time, position, *velocity, dt.min(remaining), acceleration);
+
+            let segment_vector = step.position - position;
+            let segment_distance = segment_vector.length();
+            if let Ok(segment_direction) = Dir3::try_from(segment_vector)
+                && let Some(hit) = spatial_query.cast_ray_predicate(
+                    position,
+                    segment_direction,
+                    segment_distance,
+                    true,
+                    filter,
+                    &solid,
+                )
+            {
+                let fraction = hit.distance / segment_distance;
+                let point = position + segment_direction * hit.distance;
+                commands.trigger(ProjectileHit {
+                    entity,
+                    hit_entity: hit.entity,
+                    point,
+                    normal: hit.normal,
+                    velocity: velocity.lerp(step.velocity, fraction),
+                    mass: *mass,
+                    time_of_flight: *time + fraction * step.dt,
+                    distance: *distance + hit.distance,
+                });
+                transform.translation = point;
+                commands.entity(entity).despawn();
+                continue 'projectiles;
+            }
+
+            position = step.position;
+            *velocity = step.velocity;
+            *time += step.dt;
+            *distance += segment_distance;
+            // A step cut short by the end of the tick says nothing about the next one
+            if *dt <= remaining {
+                *dt = step.next_dt;
+            }
+        }
+
+        transform.translation = position;
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{BulletPhysicsConfig, Zeroing};
+
+/// Where the bullet is at one range of a [`RangeCard`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RangeCardRow {
+    /// Horizontal distance from the muzzle in meters
+    pub range: f32,
+    /// How far the bullet is below the line of sight in meters, negative when above it
+    pub drop: f32,
+    /// Angle to hold over to make up for the drop, in radians
+    pub drop_angle: f32,
+    /// How far the bullet is to the right of the line of sight in meters
+    pub drift: f32,
+    /// Angle to hold off to the left to make up for the drift, in radians
+    pub drift_angle: f32,
+    /// Speed of the bullet in m/s
+    pub speed: f32,
+    /// Kinetic energy of the bullet in joules
+    pub energy: f32,
+    /// Time since the bullet left the muzzle in seconds
+    pub time_of_flight: f32,
+}
+
+/// Drop, drift, velocity, energy and time of flight of a round at regular ranges
+///
+/// Serializes as the array of its rows.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+#[serde(transparent)]
+pub struct RangeCard {
+    /// One row per range increment, nearest first
+    pub rows: Vec<RangeCardRow>,
+}
+
+impl RangeCard {
+    /// The card as CSV, with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "range_m,drop_m,drop_angle_rad,drift_m,drift_angle_rad,speed_mps,energy_j,time_of_flight_s\n",
+        );
+        for row in &self.rows {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                row.range,
+                row.drop,
+                row.drop_angle,
+                row.drift,
+                row.drift_angle,
+                row.speed,
+                row.energy,
+                row.time_of_flight
+            );
+        }
+        csv
+    }
+
+    /// The card as a JSON array of rows
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).expect("range cards only hold numbers");
+        json.push('\n');
+        json
+    }
+}
+
+impl BulletPhysicsConfig {
+    /// Builds a range card for a round fired in the config's air
+    ///
+    /// The bullet is flown without collisions, with the line of sight level and pointing down
+    /// -Z (or across gravity, if it doesn't point down Y), so wind is in world space as usual.
+    /// The card stops early if the bullet runs out of time or stalls.
+    ///
+    /// # Parameters
+    /// - `mass`: Mass of the bullet in kg
+    /// - `muzzle_speed`: Speed of the bullet as it leaves the muzzle (m/s)
+    /// - `increment`: Distance between rows in meters
+    /// - `max_range`: Furthest range on the card in meters
+    /// - `zeroing`: Sights to measure drop from. Without it, the bullet is fired level and drop is
+    ///   measured from the bore line
+    ///
+    /// # Returns
+    /// The card, or `None` if the zero distance is out of the round's reach
+    pub fn range_card(
+        &self,
+        mass: f32,
+        muzzle_speed: f32,
+        increment: f32,
+        max_range: f32,
+        zeroing: Option<&Zeroing>,
+    ) -> Option<RangeCard> {
+        let mut card = RangeCard::default();
+        if increment <= 0.0 {
+            return Some(card);
+        }
+
+        let up = (-self.gravity).try_normalize().unwrap_or(Vec3::Y);
+        let forward = Vec3::NEG_Z
+            .reject_from_normalized(up)
+            .try_normalize()
+            .unwrap_or_else(|| up.any_orthonormal_vector());
+        let right = forward.cross(up);
+
+        let (sight_height, elevation) = match zeroing {
+            Some(zeroing) => (
+                zeroing.sight_height,
+                zeroing.elevation(muzzle_speed, mass, self)?,
+            ),
+            None => (0.0, 0.0),
+        };
+        let acceleration = |time: f32, position: Vec3, velocity: Vec3| {
+            self.acceleration(time, position, velocity, mass)
+        };
+
+        // The line of sight runs along `forward` through the origin
+        let mut position = -up * sight_height;
+        let mut velocity = (forward * elevation.cos() + up * elevation.sin()) * muzzle_speed;
+        let mut time = 0.0;
+        let mut dt = self.time_step;
+        let range = |row: usize| increment * (row + 1) as f32;
+        while range(card.rows.len()) <= max_range && time < self.max_time {
+            let step = self
+                .integrator
+                .step(time, position, velocity, dt, acceleration);
+
+            // Fill in every row the step crossed
+            let (before, after) = (position.dot(forward), step.position.dot(forward));
+            while range(card.rows.len()) <= max_range && after >= range(card.rows.len()) {
+                let range = range(card.rows.len());
+                let fraction = (range - before) / (after - before);
+                let point = position.lerp(step.position, fraction);
+                let point_velocity = velocity.lerp(step.velocity, fraction);
+                let (drop, drift) = (-point.dot(up), point.dot(right));
+                card.rows.push(RangeCardRow {
+                    range,
+                    drop,
+                    drop_angle: drop.atan2(range),
+                    drift,
+                    drift_angle: drift.atan2(range),
+                    speed: point_velocity.length(),
+                    energy: 0.5 * mass * point_velocity.length_squared(),
+                    time_of_flight: time + fraction * step.dt,
+                });
+            }
+
+            position = step.position;
+            velocity = step.velocity;
+            time += step.dt;
+            dt = step.next_dt;
+            if velocity.dot(forward) <= 0.0 {
+                break;
+            }
+        }
+        Some(card)
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+
+/// Which points of the bullet's path are kept in `trajectory_points`
+///
+/// The start point, every impact, ricochet and exit point, and the final position are always
+/// kept, unless recording is turned off entirely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrajectoryRecording {
+    /// Don't record the path at all
+    None,
+    /// Record the position after every `n`th integration step
+    EveryNSteps(usize),
+    /// Record the position at most once every this many seconds of flight
+    Interval(f32),
+    /// Drop the points that are within `tolerance` meters of the simplified path
+    /// (Douglas–Peucker). Steps are simplified in windows of up to 1024 as the bullet flies, so
+    /// only the current window is held in memory.
+    Simplified {
+        /// Largest distance in meters a dropped point may be from the simplified path
+        tolerance: f32,
+    },
+}
+
+impl Default for TrajectoryRecording {
+    fn default() -> Self {
+        Self::EveryNSteps(1)
+    }
+}
+
+/// Most steps [`TrajectoryRecording::Simplified`] holds before simplifying them. Long straight
+/// stretches keep a point every this many steps.
+const SIMPLIFY_WINDOW: usize = 1024;
+
+/// Collects trajectory points during a simulation according to a [`TrajectoryRecording`]
+pub(crate) struct TrajectoryRecorder {
+    recording: TrajectoryRecording,
+    points: Vec<Vec3>,
+    /// Steps waiting to be simplified, starting from the last point that was kept
+    pending: Vec<Vec3>,
+    steps: usize,
+    last_time: f32,
+}
+
+impl TrajectoryRecorder {
+    pub(crate) fn new(recording: TrajectoryRecording, start: Vec3) -> Self {
+        let mut recorder = Self {
+            recording,
+            points: Vec::new(),
+            pending: Vec::new(),
+            steps: 0,
+            last_time: 0.0,
+        };
+        recorder.keyframe(start);
+        recorder
+    }
+
+    /// Records the position at the end of an integration step, if the policy wants it
+    pub(crate) fn step(&mut self, position: Vec3, time: f32) {
+        self.steps += 1;
+        let record = match self.recording {
+            TrajectoryRecording::None => false,
+            TrajectoryRecording::EveryNSteps(n) => self.steps.is_multiple_of(n.max(1)),
+            TrajectoryRecording::Interval(interval) => time - self.last_time >= interval,
+            TrajectoryRecording::Simplified { .. } => true,
+        };
+        if record {
+            self.record(position);
+            self.last_time = time;
+        }
+    }
+
+    /// Records a point that is always kept, like an impact or the end of the path
+    pub(crate) fn keyframe(&mut self, point: Vec3) {
+        if self.recording == TrajectoryRecording::None {
+            return;
+        }
+        if self.pending.last().or(self.points.last()) != Some(&point) {
+            self.record(point);
+        }
+        // Simplifying up to here keeps the point
+        if let TrajectoryRecording::Simplified { tolerance } = self.recording {
+            self.simplify(tolerance);
+        }
+    }
+
+    /// The recorded points, ending at `end`
+    pub(crate) fn finish(mut self, end: Vec3) -> Vec<Vec3> {
+        self.keyframe(end);
+        self.points
+    }
+
+    fn record(&mut self, point: Vec3) {
+        match self.recording {
+            TrajectoryRecording::Simplified { tolerance } => {
+                self.pending.push(point);
+                if self.pending.len() >= SIMPLIFY_WINDOW {
+                    self.simplify(tolerance);
+                }
+            }
+            _ => self.points.push(point),
+        }
+    }
+
+    /// Keeps the pending points needed to stay within `tolerance` of the path, always including
+    /// the last one, which starts the next window
+    fn simplify(&mut self, tolerance: f32) {
+        let Some(&last) = self.pending.last() else {
+            return;
+        };
+        let mut keep = vec![false; self.pending.len()];
+        keep[0] = true;
+        keep[self.pending.len() - 1] = true;
+        douglas_peucker(
+            &self.pending,
+            0,
+            self.pending.len() - 1,
+            tolerance,
+            &mut keep,
+        );
+
+        // Past the first window, the first point was kept by the previous one
+        let kept = if self.points.is_empty() { 0 } else { 1 };
+        self.points.extend(
+            self.pending
+                .iter()
+                .zip(keep)
+                .skip(kept)
+                .filter_map(|(&point, keep)| keep.then_some(point)),
+        );
+        self.pending.clear();
+        self.pending.push(last);
+    }
+}
+
+/// Marks the points between `first` and `last` needed to stay within `tolerance` of the path
+fn douglas_peucker(points: &[Vec3], first: usize, last: usize, tolerance: f32, keep: &mut [bool]) {
+    let mut ranges = vec![(first, last)];
+    while let Some((first, last)) = ranges.pop() {
+        let (start, end) = (points[first], points[last]);
+        let Some((index, distance)) = (first + 1..last)
+            .map(|index| (index, distance_to_segment(points[index], start, end)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+        if distance > tolerance {
+            keep[index] = true;
+            ranges.push((first, index));
+            ranges.push((index, last));
+        }
+    }
+}
+
+fn distance_to_segment(point: Vec3, start: Vec3, end: Vec3) -> f32 {
+    let segment = end - start;
+    let t = (point - start).dot(segment) / segment.length_squared().max(f32::EPSILON);
+    point.distance(start + segment * t.clamp(0.0, 1.0))
+}
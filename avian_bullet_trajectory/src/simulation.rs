@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    BulletPhysicsConfig, BulletTrajectoryResult, CollisionWorld, Penetration, Ricochet,
+    TrajectoryRecording, integrator::Step, recording::TrajectoryRecorder,
+};
+
+/// How far above a surface a ricocheting bullet continues, so it doesn't hit the same spot again
+const RICOCHET_OFFSET: f32 = 0.001;
+/// Extra radius of the broad phase sweep, so colliders the path only grazes still get ray cast
+const BROAD_PHASE_MARGIN: f32 = 0.01;
+
+/// Simulates a bullet trajectory against any [`CollisionWorld`], without needing an ECS world
+///
+/// Works like [`BulletTrajectory::simulate_bullet_with_materials`](crate::BulletTrajectory):
+/// the bullet ricochets off and passes through colliders the world has a
+/// [`BallisticMaterial`](crate::BallisticMaterial) for, and stops on the rest.
+///
+/// # Parameters
+/// - `world`: Colliders to simulate against
+/// - `start_position`: Initial position of the bullet
+/// - `initial_velocity`: Initial velocity vector of the bullet (m/s)
+/// - `mass`: Mass of the bullet in kg
+/// - `config`: Physics configuration
+///
+/// # Returns
+/// A `BulletTrajectoryResult` containing hit information, trajectory data and every ricochet
+/// and penetration along the way
+pub fn simulate_trajectory(
+    world: &impl CollisionWorld,
+    start_position: Vec3,
+    initial_velocity: Vec3,
+    mass: f32,
+    config: &BulletPhysicsConfig,
+) -> BulletTrajectoryResult {
+    let mut position = start_position;
+    let mut velocity = initial_velocity;
+    let mut recorder = TrajectoryRecorder::new(config.recording, position);
+    let mut penetrations = Vec::new();
+    let mut ricochets = Vec::new();
+    let mut time = 0.0;
+    let mut total_distance = 0.0;
+
+    let acceleration = |time: f32, position: Vec3, velocity: Vec3| {
+        config.acceleration(time, position, velocity, mass)
+    };
+
+    // Steps integrated ahead of the bullet, and how many of them the broad phase found clear
+    let mut lookahead = VecDeque::new();
+    let mut clear_steps = 0;
+
+    let mut dt = config.time_step;
+    while time < config.max_time && total_distance < config.max_distance {
+        // Integrate a stretch of steps ahead and check them all with one sweep, so open air
+        // costs a single query instead of a ray cast per step
+        if lookahead.is_empty() {
+            let (mut ahead_time, mut ahead_position, mut ahead_velocity, mut ahead_dt) =
+                (time, position, velocity, dt);
+            for _ in 0..config.broad_phase_steps.max(1) {
+                let step = config.integrator.step(
+                    ahead_time,
+                    ahead_position,
+                    ahead_velocity,
+                    ahead_dt,
+                    acceleration,
+                );
+                ahead_time += step.dt;
+                ahead_position = step.position;
+                ahead_velocity = step.velocity;
+                ahead_dt = step.next_dt;
+                lookahead.push_back(step);
+                if ahead_time >= config.max_time {
+                    break;
+                }
+            }
+
+            clear_steps = if config.broad_phase_steps > 0
+                && path_is_clear(world, position, &lookahead, &|entity| {
+                    can_hit(world, config.skip_sensors, &penetrations, entity)
+                }) {
+                lookahead.len()
+            } else {
+                0
+            };
+        }
+        let Some(step) = lookahead.pop_front() else {
+            break;
+        };
+        let cleared = clear_steps > 0;
+        clear_steps = clear_steps.saturating_sub(1);
+
+        // Check for collision along the path segment
+        let segment_vector = step.position - position;
+        let segment_distance = segment_vector.length();
+
+        // Convert Vec3 to Dir3 for the cast_ray function
+        if !cleared
+            && let Ok(segment_direction) = Dir3::try_from(segment_vector)
+            && let Some(hit) =
+                world.cast_ray(position, segment_direction, segment_distance, &|entity| {
+                    can_hit(world, config.skip_sensors, &penetrations, entity)
+                })
+        {
+            // Whatever happens next, the steps integrated ahead no longer apply
+            lookahead.clear();
+
+            // We hit something!
+            let hit_point = position + segment_direction * hit.distance;
+            let fraction = hit.distance / segment_distance;
+            let impact_velocity = velocity.lerp(step.velocity, fraction);
+            recorder.keyframe(hit_point);
+
+            let material = world.material(hit.entity);
+
+            // Grazing hits bounce off the surface. Hits without a normal to bounce off, like ones
+            // from inside a collider, are left to pass through or stop
+            if let Some(material) = material
+                && ricochets.len() < config.max_ricochets
+                && let Ok(normal) = Dir3::new(hit.normal)
+            {
+                // Make sure the normal faces back along the incoming path
+                let normal = if normal.dot(impact_velocity) > 0.0 {
+                    -normal.as_vec3()
+                } else {
+                    normal.as_vec3()
+                };
+                let normal_velocity = impact_velocity.project_onto_normalized(normal);
+                let impact_angle = (normal_velocity.length() / impact_velocity.length())
+                    .min(1.0)
+                    .asin();
+
+                if impact_angle < material.ricochet_angle {
+                    let tangent_velocity = impact_velocity - normal_velocity;
+                    let outgoing_velocity = tangent_velocity * (1.0 - material.friction)
+                        - normal_velocity * material.restitution;
+                    ricochets.push(Ricochet {
+                        entity: hit.entity,
+                        material,
+                        point: hit_point,
+                        normal,
+                        incoming_velocity: impact_velocity,
+                        outgoing_velocity,
+                    });
+
+                    // Carry on from just above the surface
+                    position = hit_point + normal * RICOCHET_OFFSET;
+                    velocity = outgoing_velocity;
+                    time += fraction * step.dt;
+                    total_distance += hit.distance;
+                    continue;
+                }
+            }
+
+            if let Some(material) = material
+                && let Some(exit_point) = find_exit(
+                    world,
+                    hit.entity,
+                    hit_point,
+                    segment_direction,
+                    config.max_penetration_depth,
+                )
+            {
+                let thickness = exit_point.distance(hit_point);
+                let remaining_energy = 0.5 * mass * impact_velocity.length_squared()
+                    - material.penetration_resistance * thickness;
+
+                if remaining_energy > 0.0 {
+                    let exit_velocity =
+                        impact_velocity.normalize() * (2.0 * remaining_energy / mass).sqrt();
+                    penetrations.push(Penetration {
+                        entity: hit.entity,
+                        material,
+                        entry_point: hit_point,
+                        entry_velocity: impact_velocity,
+                        exit_point,
+                        exit_velocity,
+                    });
+                    recorder.keyframe(exit_point);
+
+                    // Carry on from the far side of the collider
+                    position = exit_point;
+                    velocity = exit_velocity;
+                    time += fraction * step.dt + thickness / impact_velocity.length();
+                    total_distance += hit.distance + thickness;
+                    continue;
+                }
+            }
+
+            return BulletTrajectoryResult {
+                hit_entity: Some(hit.entity),
+                hit_material: material,
+                hit_point,
+                distance: total_distance + hit.distance,
+                time_of_flight: time + fraction * step.dt,
+                trajectory_points: recorder.finish(hit_point),
+                impact_velocity,
+                impact_energy: 0.5 * mass * impact_velocity.length_squared(),
+                impact_momentum: mass * impact_velocity,
+                penetrations,
+                ricochets,
+            };
+        }
+
+        // Update for next iteration
+        position = step.position;
+        velocity = step.velocity;
+        time += step.dt;
+        dt = step.next_dt;
+        total_distance += segment_distance;
+
+        recorder.step(position, time);
+    }
+
+    // No hit found within simulation limits
+    BulletTrajectoryResult {
+        hit_entity: None,
+        hit_material: None,
+        hit_point: position,
+        distance: total_distance,
+        time_of_flight: time,
+        trajectory_points: recorder.finish(position),
+        impact_velocity: velocity,
+        impact_energy: 0.5 * mass * velocity.length_squared(),
+        impact_momentum: mass * velocity,
+        penetrations,
+        ricochets,
+    }
+}
+
+/// Simulates a simple ballistic trajectory without air resistance against any [`CollisionWorld`]
+///
+/// Flies through sensors, like [`simulate_trajectory`] does by default.
+///
+/// # Parameters
+/// - `world`: Colliders to simulate against
+/// - `start_position`: Initial position of the bullet
+/// - `initial_velocity`: Initial velocity vector of the bullet (m/s)
+/// - `gravity`: Gravity acceleration vector
+/// - `recording`: Which points of the path to keep
+///
+/// # Returns
+/// A `BulletTrajectoryResult` containing hit information and trajectory data
+pub fn simulate_simple_trajectory(
+    world: &impl CollisionWorld,
+    start_position: Vec3,
+    initial_velocity: Vec3,
+    gravity: Vec3,
+    recording: TrajectoryRecording,
+) -> BulletTrajectoryResult {
+    let time_step = 0.01; // 10ms steps for simple simulation
+    let max_time = 30.0;
+    let max_distance = 10000.0;
+
+    let mut position = start_position;
+    let mut velocity = initial_velocity;
+    let mut recorder = TrajectoryRecorder::new(recording, position);
+    let mut time = 0.0;
+    let mut total_distance = 0.0;
+
+    while time < max_time && total_distance < max_distance {
+        // Simple physics: v = v0 + at, s = s0 + vt
+        let new_velocity = velocity + gravity * time_step;
+        let new_position = position + velocity * time_step;
+
+        // Check for collision
+        let segment_vector = new_position - position;
+        let segment_distance = segment_vector.length();
+
+        if segment_distance > 0.0 {
+            // Convert Vec3 to Dir3 for the cast_ray function
+            if let Ok(segment_direction) = Dir3::try_from(segment_vector)
+                && let Some(hit) =
+                    world.cast_ray(position, segment_direction, segment_distance, &|entity| {
+                        can_hit(world, true, &[], entity)
+                    })
+            {
+                let hit_point = position + segment_direction * hit.distance;
+
+                return BulletTrajectoryResult {
+                    hit_entity: Some(hit.entity),
+                    hit_material: None,
+                    hit_point,
+                    distance: total_distance + hit.distance,
+                    time_of_flight: time + hit.distance / velocity.length(),
+                    trajectory_points: recorder.finish(hit_point),
+                    impact_velocity: velocity,
+                    impact_energy: 0.0,
+                    impact_momentum: Vec3::ZERO,
+                    penetrations: Vec::new(),
+                    ricochets: Vec::new(),
+                };
+            }
+        }
+
+        position = new_position;
+        velocity = new_velocity;
+        time += time_step;
+        total_distance += segment_distance;
+
+        recorder.step(position, time);
+    }
+
+    BulletTrajectoryResult {
+        hit_entity: None,
+        hit_material: None,
+        hit_point: position,
+        distance: total_distance,
+        time_of_flight: time,
+        trajectory_points: recorder.finish(position),
+        impact_velocity: velocity,
+        impact_energy: 0.0,
+        impact_momentum: Vec3::ZERO,
+        penetrations: Vec::new(),
+        ricochets: Vec::new(),
+    }
+}
+
+/// Whether a bullet can hit `entity`. Colliders that were passed through can't be hit again,
+/// and sensors can't be hit when `skip_sensors` is on.
+fn can_hit(
+    world: &impl CollisionWorld,
+    skip_sensors: bool,
+    penetrations: &[Penetration],
+    entity: Entity,
+) -> bool {
+    let passed_through = penetrations
+        .iter()
+        .any(|penetration| penetration.entity == entity);
+    let flies_through = skip_sensors && world.is_sensor(entity);
+    !passed_through && !flies_through
+}
+
+/// Whether the path from `start` through `steps` misses everything, checked with a single sphere
+/// swept along the chord, wide enough to cover how far the path curves away from it
+fn path_is_clear(
+    world: &impl CollisionWorld,
+    start: Vec3,
+    steps: &VecDeque<Step>,
+    predicate: &dyn Fn(Entity) -> bool,
+) -> bool {
+    let Some(end) = steps.back().map(|step| step.position) else {
+        return true;
+    };
+    let chord = end - start;
+    let Ok(direction) = Dir3::try_from(chord) else {
+        return false;
+    };
+    let length = chord.length();
+
+    // The path is straight between steps, so its corners are what stray furthest
+    let sag = steps
+        .iter()
+        .map(|step| {
+            let along = (step.position - start).dot(*direction).clamp(0.0, length);
+            step.position.distance(start + direction * along)
+        })
+        .fold(0.0, f32::max);
+    world.sweep_is_clear(
+        start,
+        direction,
+        length,
+        sag + BROAD_PHASE_MARGIN,
+        predicate,
+    )
+}
+
+/// Finds where a bullet entering `entity` at `entry_point` comes out again by casting back
+/// from `max_depth` further along its path. Returns `None` if the collider is thicker than that.
+fn find_exit(
+    world: &impl CollisionWorld,
+    entity: Entity,
+    entry_point: Vec3,
+    direction: Dir3,
+    max_depth: f32,
+) -> Option<Vec3> {
+    let probe_start = entry_point + direction * max_depth;
+    world
+        .cast_ray(probe_start, -direction, max_depth, &|candidate| {
+            candidate == entity
+        })
+        // A hit at distance zero means the probe started inside the collider
+        .filter(|hit| hit.distance > 0.0)
+        .map(|hit| probe_start - direction * hit.distance)
+}
@@ -0,0 +1,397 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::BulletPhysicsConfig;
+
+/// How close the bullet has to pass to the target, in meters
+const TOLERANCE: f32 = 0.001;
+/// Elevations tried when looking for the angle that carries the bullet highest over the target
+const SCAN_STEPS: usize = 32;
+/// Bisection and golden-section iterations, enough for micro-radian precision
+const ANGLE_ITERATIONS: usize = 32;
+/// Corrections made to the lead before giving up
+const LEAD_ITERATIONS: usize = 8;
+/// Refinements of the predicted intercept time before giving up
+const INTERCEPT_ITERATIONS: usize = 16;
+/// How closely the bullet's time of flight has to match the predicted intercept time, in seconds
+const INTERCEPT_TOLERANCE: f32 = 0.0001;
+
+/// The aim needed to hit a target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaunchSolution {
+    /// Direction to fire in
+    pub direction: Dir3,
+    /// Angle above the horizontal in radians
+    pub elevation: f32,
+    /// Horizontal angle to aim to the right of the target in radians, to make up for wind
+    pub lead: f32,
+    /// Time until the bullet reaches the target in seconds
+    pub time_of_flight: f32,
+    /// Velocity of the bullet at the target
+    pub impact_velocity: Vec3,
+}
+
+/// Both ways of hitting a target, if they exist
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LaunchSolutions {
+    /// The flatter, faster arc
+    pub low: Option<LaunchSolution>,
+    /// The lobbed arc that comes down on the target from above
+    pub high: Option<LaunchSolution>,
+}
+
+/// The aim needed to hit a moving target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterceptSolution {
+    /// How to aim at the point where the bullet meets the target
+    pub launch: LaunchSolution,
+    /// Where the target will be when the bullet reaches it
+    pub intercept_point: Vec3,
+    /// Time until the bullet reaches the target in seconds
+    pub intercept_time: f32,
+}
+
+impl BulletPhysicsConfig {
+    /// Solves for the elevation and lead needed to hit `target` from `muzzle`
+    ///
+    /// The bullet is flown without collisions using the config's gravity, drag, environment and
+    /// integrator, and the aim is refined until it passes within a millimeter of the target, or
+    /// as close as the simulation's precision allows on very long flights.
+    ///
+    /// # Parameters
+    /// - `muzzle`: Position the bullet is fired from
+    /// - `muzzle_speed`: Speed of the bullet as it leaves the muzzle (m/s)
+    /// - `mass`: Mass of the bullet in kg
+    /// - `target`: Point to hit
+    ///
+    /// # Returns
+    /// The low and high arcs, each `None` if the target is out of reach
+    ///
+    /// # Cost
+    /// Every guess at the aim flies the bullet all the way to the target. The low arc takes up to
+    /// about 40 flights, the high arc about 130 as its peak has to be searched for, and both take
+    /// up to 8 times that when wind makes the lead need correcting. That's far too much to
+    /// solve for every shot, so solve once and keep the result for as long as the weapon and
+    /// target stay the same:
+    ///
+    /// ```no_run
+    /// # use avian_bullet_trajectory::BulletPhysicsConfig;
+    /// # use bevy::prelude::*;
+    /// /// Elevation that hits 100 m out, solved once when the weapon is picked up
+    /// #[derive(Component)]
+    /// struct ZeroElevation(f32);
+    ///
+    /// let config = BulletPhysicsConfig::caliber_556();
+    /// let solutions = config.solve_launch(Vec3::ZERO, 920.0, 0.004, Vec3::new(0.0, 0.0, -100.0));
+    /// let zero = ZeroElevation(solutions.low.map_or(0.0, |low| low.elevation));
+    /// ```
+    pub fn solve_launch(
+        &self,
+        muzzle: Vec3,
+        muzzle_speed: f32,
+        mass: f32,
+        target: Vec3,
+    ) -> LaunchSolutions {
+        let Some(problem) = Problem::new(self, muzzle, muzzle_speed, mass, target) else {
+            return LaunchSolutions::default();
+        };
+        LaunchSolutions {
+            low: problem.solve(Branch::Low),
+            high: problem.solve(Branch::High),
+        }
+    }
+
+    /// Solves for just the low arc of [`BulletPhysicsConfig::solve_launch`], skipping the search
+    /// for the high arc's peak and its long, lobbed flights
+    pub(crate) fn solve_low_arc(
+        &self,
+        muzzle: Vec3,
+        muzzle_speed: f32,
+        mass: f32,
+        target: Vec3,
+    ) -> Option<LaunchSolution> {
+        Problem::new(self, muzzle, muzzle_speed, mass, target)?.solve(Branch::Low)
+    }
+
+    /// Solves for the aim needed to hit a target moving with `target_velocity`
+    ///
+    /// The target is assumed to keep its velocity, or to keep accelerating at
+    /// `target_acceleration`. The intercept point is refined by solving for the low arc to the
+    /// predicted position until the bullet's time of flight matches the prediction.
+    ///
+    /// # Parameters
+    /// - `muzzle`: Position the bullet is fired from
+    /// - `muzzle_speed`: Speed of the bullet as it leaves the muzzle (m/s)
+    /// - `mass`: Mass of the bullet in kg
+    /// - `target`: Current position of the target
+    /// - `target_velocity`: Current velocity of the target, like its `LinearVelocity`
+    /// - `target_acceleration`: Optional acceleration of the target
+    ///
+    /// # Returns
+    /// The aim and predicted intercept, or `None` if the bullet can't catch the target
+    pub fn solve_intercept(
+        &self,
+        muzzle: Vec3,
+        muzzle_speed: f32,
+        mass: f32,
+        target: Vec3,
+        target_velocity: Vec3,
+        target_acceleration: Option<Vec3>,
+    ) -> Option<InterceptSolution> {
+        let acceleration = target_acceleration.unwrap_or(Vec3::ZERO);
+        let predict =
+            |time: f32| target + target_velocity * time + 0.5 * acceleration * time * time;
+
+        // Start from how long the bullet would take to reach where the target is now
+        let mut time = muzzle.distance(target) / muzzle_speed;
+        for _ in 0..INTERCEPT_ITERATIONS {
+            let intercept_point = predict(time);
+            let launch = Problem::new(self, muzzle, muzzle_speed, mass, intercept_point)?
+                .solve(Branch::Low)?;
+            if (launch.time_of_flight - time).abs() < INTERCEPT_TOLERANCE {
+                return Some(InterceptSolution {
+                    launch,
+                    intercept_point,
+                    intercept_time: launch.time_of_flight,
+                });
+            }
+            time = launch.time_of_flight;
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Branch {
+    Low,
+    High,
+}
+
+/// Where the bullet crossed the plane through the target, facing the muzzle
+struct Crossing {
+    /// Offset from the target
+    miss: Vec3,
+    time: f32,
+    velocity: Vec3,
+}
+
+struct Problem<'a> {
+    config: &'a BulletPhysicsConfig,
+    muzzle: Vec3,
+    muzzle_speed: f32,
+    mass: f32,
+    target: Vec3,
+    /// Opposite to gravity
+    up: Vec3,
+    /// Horizontal direction from the muzzle to the target
+    bearing: Vec3,
+    /// Horizontal direction to the right of the bearing
+    right: Vec3,
+    /// Horizontal distance to the target
+    range: f32,
+}
+
+impl<'a> Problem<'a> {
+    fn new(
+        config: &'a BulletPhysicsConfig,
+        muzzle: Vec3,
+        muzzle_speed: f32,
+        mass: f32,
+        target: Vec3,
+    ) -> Option<Self> {
+        let up = (-config.gravity).try_normalize().unwrap_or(Vec3::Y);
+        let offset = target - muzzle;
+        let horizontal = offset - offset.project_onto_normalized(up);
+        let range = horizontal.length();
+        let bearing = horizontal.try_normalize()?;
+        Some(Self {
+            config,
+            muzzle,
+            muzzle_speed,
+            mass,
+            target,
+            up,
+            bearing,
+            right: bearing.cross(up),
+            range,
+        })
+    }
+
+    fn direction(&self, elevation: f32, lead: f32) -> Vec3 {
+        let horizontal = self.bearing * lead.cos() + self.right * lead.sin();
+        horizontal * elevation.cos() + self.up * elevation.sin()
+    }
+
+    /// Flies the bullet until it crosses the target's range
+    ///
+    /// Returns `None` if it never gets there, or falls below the target on the way.
+    fn fly(&self, elevation: f32, lead: f32) -> Option<Crossing> {
+        let config = self.config;
+        let acceleration = |time: f32, position: Vec3, velocity: Vec3| {
+            config.acceleration(time, position, velocity, self.mass)
+        };
+        let along = |position: Vec3| (position - self.target).dot(self.bearing);
+
+        let mut position = self.muzzle;
+        let mut velocity = self.direction(elevation, lead) * self.muzzle_speed;
+        let mut time = 0.0;
+        let mut distance = 0.0;
+        let mut dt = config.time_step;
+        while time < config.max_time && distance < config.max_distance {
+            let step = config
+                .integrator
+                .step(time, position, velocity, dt, acceleration);
+
+            let (before, after) = (along(position), along(step.position));
+            if after >= 0.0 {
+                let fraction = before / (before - after);
+                return Some(Crossing {
+                    miss: position.lerp(step.position, fraction) - self.target,
+                    time: time + fraction * step.dt,
+                    velocity: velocity.lerp(step.velocity, fraction),
+                });
+            }
+
+            distance += step.position.distance(position);
+            position = step.position;
+            velocity = step.velocity;
+            time += step.dt;
+            dt = step.next_dt;
+
+            // Stalled, or already falling past the target's height and can only miss it
+            let below = (position - self.target).dot(self.up) < -TOLERANCE;
+            if velocity.dot(self.bearing) <= 0.0 || (below && velocity.dot(self.up) < 0.0) {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// How far above the target the bullet passes, or negative infinity if it doesn't get there
+    fn height(&self, elevation: f32, lead: f32) -> f32 {
+        self.fly(elevation, lead)
+            .map_or(f32::NEG_INFINITY, |crossing| crossing.miss.dot(self.up))
+    }
+
+    /// The elevation that carries the bullet highest over the target
+    fn peak(&self, lead: f32) -> (f32, f32) {
+        let limit = FRAC_PI_2 - 0.001;
+        let angle = |step: usize| -limit + 2.0 * limit * step as f32 / SCAN_STEPS as f32;
+
+        // Scan coarsely first, since most elevations don't reach the target at all
+        let (best, _) = (0..=SCAN_STEPS)
+            .map(|step| (step, self.height(angle(step), lead)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or_default();
+        let (mut low, mut high) = (
+            angle(best.saturating_sub(1)),
+            angle((best + 1).min(SCAN_STEPS)),
+        );
+
+        // Then narrow it down with a golden-section search
+        const RATIO: f32 = 0.618_034;
+        for _ in 0..ANGLE_ITERATIONS {
+            let a = high - RATIO * (high - low);
+            let b = low + RATIO * (high - low);
+            if self.height(a, lead) < self.height(b, lead) {
+                low = a;
+            } else {
+                high = b;
+            }
+        }
+        let elevation = (low + high) / 2.0;
+        (elevation, self.height(elevation, lead))
+    }
+
+    /// Brackets the low arc by raising the aim from straight at the target until the bullet
+    /// passes over it
+    ///
+    /// This is much cheaper than finding the peak, since only flat, short flights are tried.
+    /// Returns `None` once raising the aim stops helping, so the peak has to be searched for.
+    fn bracket_low(&self, lead: f32) -> Option<(f32, f32)> {
+        let limit = FRAC_PI_2 - 0.001;
+        let offset = self.target - self.muzzle;
+        let mut below = offset.dot(self.up).atan2(self.range);
+        let mut previous = self.height(below, lead);
+        let mut step = 0.001;
+        while below + step < limit {
+            let elevation = below + step;
+            let height = self.height(elevation, lead);
+            if height >= 0.0 {
+                return Some((below, elevation));
+            }
+            if height < previous {
+                return None;
+            }
+            (below, previous) = (elevation, height);
+            step *= 2.0;
+        }
+        None
+    }
+
+    /// Bisects for the elevation between `below` and `above` where the bullet passes level with
+    /// the target, given the bullet passes below it at `below` and above it at `above`
+    ///
+    /// Long flights pick up rounding noise, so if the target can't be hit to within the
+    /// tolerance, this settles for the closest elevation that still passes over it.
+    fn bisect(&self, mut below: f32, mut above: f32, lead: f32) -> f32 {
+        for _ in 0..ANGLE_ITERATIONS {
+            let middle = (below + above) / 2.0;
+            let height = self.height(middle, lead);
+            if height.abs() < TOLERANCE {
+                return middle;
+            }
+            if height < 0.0 {
+                below = middle;
+            } else {
+                above = middle;
+            }
+        }
+        above
+    }
+
+    fn solve(&self, branch: Branch) -> Option<LaunchSolution> {
+        let limit = FRAC_PI_2 - 0.001;
+        let mut lead = 0.0;
+        let mut solution = None;
+        for _ in 0..LEAD_ITERATIONS {
+            let bracket = match branch {
+                Branch::Low => self.bracket_low(lead),
+                Branch::High => None,
+            };
+            let (below, above) = match bracket {
+                Some(bracket) => bracket,
+                None => {
+                    let (peak, height) = self.peak(lead);
+                    if height < 0.0 {
+                        break;
+                    }
+                    match branch {
+                        Branch::Low => (-limit, peak),
+                        Branch::High => (limit, peak),
+                    }
+                }
+            };
+            let elevation = self.bisect(below, above, lead);
+            let Some(crossing) = self.fly(elevation, lead) else {
+                break;
+            };
+            solution = Some(LaunchSolution {
+                direction: Dir3::new(self.direction(elevation, lead)).ok()?,
+                elevation,
+                lead,
+                time_of_flight: crossing.time,
+                impact_velocity: crossing.velocity,
+            });
+
+            // Aim off against whatever pushed the bullet sideways
+            let miss = crossing.miss.dot(self.right);
+            if miss.abs() < TOLERANCE {
+                break;
+            }
+            lead -= (miss / self.range).atan();
+        }
+        solution
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// How the projectiles of a multi-projectile shot (like a shotgun's pellets) are spread out.
+///
+/// Pellets are scattered evenly over the cone around the aim direction. The same seed always
+/// produces the same pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadPattern {
+    /// Angle between the aim direction and the edge of the cone in radians
+    pub cone_angle: f32,
+    /// Number of projectiles fired per shot
+    pub pellet_count: usize,
+    /// Seed for the random number generator scattering the pellets
+    pub seed: u64,
+}
+
+impl SpreadPattern {
+    /// `pellet_count` pellets scattered up to `cone_angle` radians from the aim direction.
+    ///
+    /// `seed` picks the pattern: pass the same seed for the same pattern every shot, like a choke
+    /// that always throws the same spread, or a fresh one for a new pattern each time.
+    pub fn new(cone_angle: f32, pellet_count: usize, seed: u64) -> Self {
+        Self {
+            cone_angle,
+            pellet_count,
+            seed,
+        }
+    }
+
+    /// The initial velocity of every pellet when the shot is fired with `velocity`
+    pub fn velocities(&self, velocity: Vec3) -> Vec<Vec3> {
+        let Ok(direction) = Dir3::new(velocity) else {
+            return vec![velocity; self.pellet_count];
+        };
+        let speed = velocity.length();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        (0..self.pellet_count)
+            .map(|_| scatter(&mut rng, direction, self.cone_angle) * speed)
+            .collect()
+    }
+
+    /// `direction` strayed up to `cone_angle` radians off, like a single bullet from an
+    /// inaccurate weapon
+    ///
+    /// Scattered the same way as the pellets of a pattern. The same seed always gives the same
+    /// direction.
+    pub fn perturb(direction: Dir3, cone_angle: f32, seed: u64) -> Dir3 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Dir3::new(scatter(&mut rng, direction, cone_angle)).unwrap_or(direction)
+    }
+}
+
+/// A random direction within `cone_angle` radians of `direction`
+fn scatter(rng: &mut StdRng, direction: Dir3, cone_angle: f32) -> Vec3 {
+    let (side, up) = direction.any_orthonormal_pair();
+    let min_cos = cone_angle.clamp(0.0, std::f32::consts::PI).cos();
+
+    // Uniform over the cone's solid angle rather than its angle, so pellets don't bunch up in
+    // the middle
+    let cos = rng.random_range(min_cos..=1.0);
+    let sin = (1.0 - cos * cos).max(0.0).sqrt();
+    let (around_sin, around_cos) = rng.random_range(0.0..std::f32::consts::TAU).sin_cos();
+    direction * cos + (side * around_cos + up * around_sin) * sin
+}
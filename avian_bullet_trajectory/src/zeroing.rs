@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::{AirSample, BallisticEnvironment, BulletPhysicsConfig};
+
+/// How a weapon's sights are set up relative to its barrel.
+///
+/// The bore sits below the line of sight and is tilted up slightly, so the bullet rises through
+/// the line of sight and comes back down onto it at the zero distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zeroing {
+    /// Distance from the center of the bore up to the line of sight in meters
+    pub sight_height: f32,
+    /// Distance at which the bullet hits the point of aim in meters
+    pub zero_distance: f32,
+}
+
+impl Zeroing {
+    /// Sights `sight_height` meters above the center of the bore, zeroed at `zero_distance`
+    /// meters from the muzzle
+    pub fn new(sight_height: f32, zero_distance: f32) -> Self {
+        Self {
+            sight_height,
+            zero_distance,
+        }
+    }
+
+    /// Angle in radians between the line of sight and the bore that zeroes the weapon
+    ///
+    /// Solved for a level shot in still air with the config's density and speed of sound, so the
+    /// zero doesn't depend on which way the wind blows. Returns `None` if the zero distance is out
+    /// of reach.
+    ///
+    /// # Parameters
+    /// - `muzzle_speed`: Speed of the bullet as it leaves the muzzle (m/s)
+    /// - `mass`: Mass of the bullet in kg
+    /// - `config`: Physics configuration of the round
+    pub fn elevation(
+        &self,
+        muzzle_speed: f32,
+        mass: f32,
+        config: &BulletPhysicsConfig,
+    ) -> Option<f32> {
+        let config = BulletPhysicsConfig {
+            environment: Arc::new(StillAir(config.environment.clone())),
+            ..config.clone()
+        };
+        let up = (-config.gravity).try_normalize().unwrap_or(Vec3::Y);
+        let forward = up.any_orthonormal_vector();
+        let solution = config.solve_low_arc(
+            -up * self.sight_height,
+            muzzle_speed,
+            mass,
+            forward * self.zero_distance,
+        )?;
+        Some(solution.elevation)
+    }
+
+    /// The muzzle position and launch direction for a shot aimed through `sight`
+    ///
+    /// # Parameters
+    /// - `sight`: Transform of the sight, looking down the line of sight
+    /// - `elevation`: Angle between the line of sight and the bore, from [`Zeroing::elevation`]
+    pub fn launch(&self, sight: &Transform, elevation: f32) -> (Vec3, Dir3) {
+        let muzzle = sight.translation + sight.down() * self.sight_height;
+        let direction = Quat::from_axis_angle(*sight.right(), elevation) * sight.forward();
+        (muzzle, direction)
+    }
+}
+
+/// Another environment's air with the wind taken out
+#[derive(Debug)]
+struct StillAir(Arc<dyn BallisticEnvironment>);
+
+impl BallisticEnvironment for StillAir {
+    fn sample(&self, position: Vec3, time: f32) -> AirSample {
+        AirSample {
+            wind: Vec3::ZERO,
+            ..self.0.sample(position, time)
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Checks that the broad phase sweep skips ray casts without changing where bullets end up.
+
+mod common;
+
+use std::cell::Cell;
+
+use avian_bullet_trajectory::{
+    BallisticMaterial, BulletPhysicsConfig, BulletTrajectoryResult, CollisionWorld, Integrator,
+    RayImpact, SimpleCollider, SimpleCollisionWorld, SimpleShape, simulate_trajectory,
+};
+use bevy::prelude::*;
+
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+
+/// Counts the queries made against a world
+struct CountingWorld<W> {
+    world: W,
+    ray_casts: Cell<usize>,
+    sweeps: Cell<usize>,
+}
+
+impl<W> CountingWorld<W> {
+    fn new(world: W) -> Self {
+        Self {
+            world,
+            ray_casts: Cell::new(0),
+            sweeps: Cell::new(0),
+        }
+    }
+
+    fn queries(&self) -> usize {
+        self.ray_casts.get() + self.sweeps.get()
+    }
+}
+
+impl<W: CollisionWorld> CollisionWorld for CountingWorld<W> {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> Option<RayImpact> {
+        self.ray_casts.set(self.ray_casts.get() + 1);
+        self.world
+            .cast_ray(origin, direction, max_distance, predicate)
+    }
+
+    fn sweep_is_clear(
+        &self,
+        origin: Vec3,
+        direction: Dir3,
+        max_distance: f32,
+        radius: f32,
+        predicate: &dyn Fn(Entity) -> bool,
+    ) -> bool {
+        self.sweeps.set(self.sweeps.get() + 1);
+        self.world
+            .sweep_is_clear(origin, direction, max_distance, radius, predicate)
+    }
+
+    fn material(&self, entity: Entity) -> Option<BallisticMaterial> {
+        self.world.material(entity)
+    }
+
+    fn is_sensor(&self, entity: Entity) -> bool {
+        self.world.is_sensor(entity)
+    }
+}
+
+/// A kilometer of ground with rows of crates on it and a plywood board
+fn map() -> SimpleCollisionWorld {
+    let mut entities = World::new();
+    let mut colliders = vec![
+        SimpleCollider::new(
+            entities.spawn_empty().id(),
+            SimpleShape::Aabb {
+                min: Vec3::new(-500.0, -1.0, -1000.0),
+                max: Vec3::new(500.0, 0.0, 0.0),
+            },
+        ),
+        SimpleCollider::new(
+            entities.spawn_empty().id(),
+            SimpleShape::Aabb {
+                min: Vec3::new(-5.0, 0.0, -40.02),
+                max: Vec3::new(5.0, 3.0, -40.0),
+            },
+        )
+        .with_material(BallisticMaterial::plywood()),
+    ];
+    for column in 0..20 {
+        for row in 0..20 {
+            let center = Vec3::new(
+                -475.0 + column as f32 * 50.0,
+                0.0,
+                -25.0 - row as f32 * 50.0,
+            );
+            let height = 2.0 + ((column * 7 + row * 13) % 10) as f32;
+            colliders.push(SimpleCollider::new(
+                entities.spawn_empty().id(),
+                SimpleShape::Aabb {
+                    min: center - Vec3::new(2.0, 0.0, 2.0),
+                    max: center + Vec3::new(2.0, height, 2.0),
+                },
+            ));
+        }
+    }
+    SimpleCollisionWorld::new(colliders)
+}
+
+fn without_broad_phase(config: &BulletPhysicsConfig) -> BulletPhysicsConfig {
+    BulletPhysicsConfig {
+        broad_phase_steps: 0,
+        ..config.clone()
+    }
+}
+
+fn assert_same(pruned: &BulletTrajectoryResult, fine: &BulletTrajectoryResult) {
+    assert_eq!(pruned.hit_entity, fine.hit_entity);
+    assert_eq!(pruned.hit_point, fine.hit_point);
+    assert_eq!(pruned.time_of_flight, fine.time_of_flight);
+    assert_eq!(pruned.impact_velocity, fine.impact_velocity);
+    assert_eq!(pruned.trajectory_points, fine.trajectory_points);
+    assert_eq!(pruned.penetrations.len(), fine.penetrations.len());
+    assert_eq!(pruned.ricochets.len(), fine.ricochets.len());
+}
+
+#[test]
+fn matches_fine_stepping() {
+    let world = map();
+    let shots = [
+        // Through the board and into the ground
+        (
+            BulletPhysicsConfig::caliber_556(),
+            0.004,
+            Vec3::new(0.0, -0.01, -1.0),
+            900.0,
+        ),
+        // Into a crate
+        (
+            BulletPhysicsConfig::caliber_9mm(),
+            0.0075,
+            Vec3::new(1.0, 0.0, -1.0),
+            375.0,
+        ),
+        // Lobbed over the crates into the ground
+        (
+            BulletPhysicsConfig {
+                integrator: Integrator::Rk4,
+                ..BulletPhysicsConfig::buckshot_00()
+            },
+            0.0035,
+            Vec3::new(0.1, 0.05, -1.0),
+            400.0,
+        ),
+        // Off the edge of the map
+        (
+            BulletPhysicsConfig {
+                integrator: Integrator::adaptive(0.0001),
+                ..BulletPhysicsConfig::caliber_762()
+            },
+            0.0095,
+            Vec3::new(0.0, 0.3, -1.0),
+            850.0,
+        ),
+    ];
+    for (config, mass, direction, speed) in shots {
+        let velocity = direction.normalize() * speed;
+        let pruned = simulate_trajectory(&world, START, velocity, mass, &config);
+        let fine =
+            simulate_trajectory(&world, START, velocity, mass, &without_broad_phase(&config));
+        assert_same(&pruned, &fine);
+    }
+}
+
+#[test]
+fn long_miss_skips_most_ray_casts() {
+    let world = CountingWorld::new(map());
+    let config = BulletPhysicsConfig::caliber_556();
+    // Steep enough to come down past the end of the map
+    let velocity = Vec3::new(0.0, 0.3, -1.0).normalize() * 900.0;
+
+    let pruned = simulate_trajectory(&world, START, velocity, 0.004, &config);
+    assert_eq!(pruned.hit_entity, None);
+    let pruned_queries = world.queries();
+
+    world.ray_casts.set(0);
+    world.sweeps.set(0);
+    simulate_trajectory(
+        &world,
+        START,
+        velocity,
+        0.004,
+        &without_broad_phase(&config),
+    );
+    assert_eq!(world.sweeps.get(), 0);
+    assert!(world.ray_casts.get() > 29_000, "{}", world.ray_casts.get());
+    assert!(pruned_queries < 1_000, "{pruned_queries}");
+}
+
+#[test]
+fn matches_fine_stepping_with_spatial_query() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -300.0, 1.0);
+    let config = BulletPhysicsConfig::caliber_762();
+    let velocity = Vec3::new(0.0, 0.0, -850.0);
+
+    let pruned = common::simulate(&mut app, START, velocity, 0.0095, config.clone());
+    let fine = common::simulate(
+        &mut app,
+        START,
+        velocity,
+        0.0095,
+        without_broad_phase(&config),
+    );
+    assert!(pruned.hit_entity.is_some());
+    assert_same(&pruned, &fine);
+}
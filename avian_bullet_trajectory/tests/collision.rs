@@ -0,0 +1,170 @@
+//! Simulates against simple planes and boxes without an app, and checks they agree with avian.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BallisticMaterial, BulletPhysicsConfig, CollisionWorld, SimpleCollider, SimpleCollisionWorld,
+    SimpleShape, TrajectoryRecording, simulate_simple_trajectory, simulate_trajectory,
+};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.004;
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -900.0);
+
+/// A box like `common::spawn_wall`, with its front face at `z = front`
+fn wall(entity: Entity, front: f32, thickness: f32) -> SimpleCollider {
+    SimpleCollider::new(
+        entity,
+        SimpleShape::Aabb {
+            min: Vec3::new(-50.0, -50.0, front - thickness),
+            max: Vec3::new(50.0, 50.0, front),
+        },
+    )
+}
+
+#[test]
+fn matches_spatial_query() {
+    let mut app = common::physics_app();
+    let entity = common::spawn_wall(&mut app, -100.0, 1.0);
+    let config = BulletPhysicsConfig::caliber_556();
+    let expected = common::simulate(&mut app, START, VELOCITY, MASS, config.clone());
+
+    let world = SimpleCollisionWorld::new([wall(entity, -100.0, 1.0)]);
+    let result = simulate_trajectory(&world, START, VELOCITY, MASS, &config);
+    assert_eq!(result.hit_entity, Some(entity));
+    assert!(result.hit_point.distance(expected.hit_point) < 1e-4);
+    assert!((result.distance - expected.distance).abs() < 1e-4);
+    assert!((result.time_of_flight - expected.time_of_flight).abs() < 1e-6);
+}
+
+#[test]
+fn lands_on_ground_plane() {
+    let ground = World::new().spawn_empty().id();
+    let world = SimpleCollisionWorld::new([SimpleCollider::new(
+        ground,
+        SimpleShape::Plane {
+            point: Vec3::ZERO,
+            normal: Dir3::Y,
+        },
+    )]);
+    let result = simulate_simple_trajectory(
+        &world,
+        START,
+        Vec3::new(0.0, 10.0, -50.0),
+        Vec3::new(0.0, -9.81, 0.0),
+        TrajectoryRecording::None,
+    );
+    assert_eq!(result.hit_entity, Some(ground));
+    assert!(result.hit_point.y.abs() < 1e-3, "{}", result.hit_point);
+    // Lands after about 2.18 s of flight
+    assert!((result.time_of_flight - 2.18).abs() < 0.02);
+}
+
+#[test]
+fn simple_trajectory_times_the_hit_within_a_step() {
+    let entity = World::new().spawn_empty().id();
+    let world = SimpleCollisionWorld::new([wall(entity, -15.0, 1.0)]);
+    let result = simulate_simple_trajectory(
+        &world,
+        START,
+        VELOCITY,
+        Vec3::ZERO,
+        TrajectoryRecording::None,
+    );
+    assert_eq!(result.hit_entity, Some(entity));
+    // The wall is hit partway through the second 10 ms step
+    assert!((result.time_of_flight - 15.0 / 900.0).abs() < 1e-6);
+}
+
+#[test]
+fn simple_trajectory_flies_through_sensors() {
+    let mut entities = World::new();
+    let [sensor, backstop] = [(); 2].map(|_| entities.spawn_empty().id());
+    let world = SimpleCollisionWorld::new([
+        wall(sensor, -15.0, 1.0).with_sensor(true),
+        wall(backstop, -30.0, 1.0),
+    ]);
+    let result = simulate_simple_trajectory(
+        &world,
+        START,
+        VELOCITY,
+        Vec3::new(0.0, -9.81, 0.0),
+        TrajectoryRecording::None,
+    );
+    assert_eq!(result.hit_entity, Some(backstop));
+}
+
+#[test]
+fn passes_through_and_ricochets_off_materials() {
+    let mut entities = World::new();
+    let [board, plate, sensor] = [(); 3].map(|_| entities.spawn_empty().id());
+    let world = SimpleCollisionWorld::new([
+        wall(board, -10.0, 0.02).with_material(BallisticMaterial::plywood()),
+        wall(sensor, -15.0, 1.0).with_sensor(true),
+        // Tilted a steep 80° away from the bullet, so it skims off
+        SimpleCollider::new(
+            plate,
+            SimpleShape::Plane {
+                point: Vec3::new(0.0, 0.0, -20.0),
+                normal: Dir3::new(Vec3::new(
+                    0.0,
+                    80f32.to_radians().sin(),
+                    80f32.to_radians().cos(),
+                ))
+                .unwrap(),
+            },
+        )
+        .with_material(BallisticMaterial::steel()),
+    ]);
+    let config = BulletPhysicsConfig {
+        max_distance: 200.0,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = simulate_trajectory(&world, START, VELOCITY, MASS, &config);
+
+    assert_eq!(result.penetrations.len(), 1);
+    assert_eq!(result.penetrations[0].entity, board);
+    assert!((result.penetrations[0].exit_point.z + 10.02).abs() < 1e-4);
+    assert_eq!(result.ricochets.len(), 1, "should skim off the plate");
+    assert_eq!(result.ricochets[0].entity, plate);
+    assert!(result.ricochets[0].outgoing_velocity.y > 0.0);
+}
+
+#[test]
+fn ray_starting_inside_box_hits_at_zero() {
+    let entity = World::new().spawn_empty().id();
+    let world = SimpleCollisionWorld::new([wall(entity, 0.0, 2.0)]);
+    let result = simulate_trajectory(
+        &world,
+        Vec3::new(0.0, 0.0, -1.0),
+        VELOCITY,
+        MASS,
+        &BulletPhysicsConfig::default(),
+    );
+    assert_eq!(result.hit_entity, Some(entity));
+    assert_eq!(result.distance, 0.0);
+}
+
+#[test]
+fn ray_from_inside_box_points_out_of_nearest_face() {
+    let entity = World::new().spawn_empty().id();
+    let world = SimpleCollisionWorld::new([SimpleCollider::new(
+        entity,
+        SimpleShape::Aabb {
+            min: Vec3::splat(-1.0),
+            max: Vec3::splat(1.0),
+        },
+    )]);
+    for (origin, normal) in [
+        (Vec3::new(0.0, 0.8, 0.1), Vec3::Y),
+        (Vec3::new(-0.9, 0.2, 0.1), Vec3::NEG_X),
+        (Vec3::new(0.1, 0.2, -0.7), Vec3::NEG_Z),
+    ] {
+        let hit = world
+            .cast_ray(origin, Dir3::NEG_Z, 10.0, &|_| true)
+            .expect("a ray from inside should hit");
+        assert_eq!(hit.distance, 0.0);
+        assert_eq!(hit.normal, normal, "from {origin}");
+    }
+}
@@ -0,0 +1,92 @@
+//! Headless physics harness shared by the integration tests.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use avian_bullet_trajectory::{
+    BallisticMaterials, BulletPhysicsConfig, BulletSpatialQuery, BulletTrajectory,
+    BulletTrajectoryResult,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*, time::TimeUpdateStrategy};
+
+/// Builds a minimal app with physics and no colliders.
+///
+/// Every update advances time by exactly one fixed tick, so runs are reproducible.
+pub fn physics_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        bevy::transform::TransformPlugin,
+        AssetPlugin::default(),
+        PhysicsPlugins::default(),
+    ))
+    .init_asset::<Mesh>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / 64.0,
+    )));
+    app.finish();
+    app.cleanup();
+    app.update();
+    app
+}
+
+/// Runs `simulate_bullet_trajectory` once against the app's spatial query pipeline.
+pub fn simulate(
+    app: &mut App,
+    start: Vec3,
+    velocity: Vec3,
+    mass: f32,
+    config: BulletPhysicsConfig,
+) -> BulletTrajectoryResult {
+    app.world_mut()
+        .run_system_once(move |spatial_query: BulletSpatialQuery| {
+            spatial_query.simulate_bullet_trajectory(
+                start,
+                velocity,
+                mass,
+                Some(config.clone()),
+                &SpatialQueryFilter::default(),
+            )
+        })
+        .expect("trajectory system should run")
+}
+
+/// Runs `simulate_bullet_with_materials` once, looking up the colliders' `BallisticMaterial`s.
+pub fn simulate_with_materials(
+    app: &mut App,
+    start: Vec3,
+    velocity: Vec3,
+    mass: f32,
+    config: BulletPhysicsConfig,
+) -> BulletTrajectoryResult {
+    app.world_mut()
+        .run_system_once(
+            move |spatial_query: BulletSpatialQuery, materials: BallisticMaterials| {
+                spatial_query.simulate_bullet_with_materials(
+                    start,
+                    velocity,
+                    mass,
+                    Some(config.clone()),
+                    &SpatialQueryFilter::default(),
+                    &materials,
+                )
+            },
+        )
+        .expect("trajectory system should run")
+}
+
+/// Spawns a static wall facing +Z with its front face at `z = front`, and lets the
+/// spatial query pipeline pick it up.
+pub fn spawn_wall(app: &mut App, front: f32, thickness: f32) -> Entity {
+    let wall = app
+        .world_mut()
+        .spawn((
+            RigidBody::Static,
+            Collider::cuboid(100.0, 100.0, thickness),
+            Transform::from_xyz(0.0, 0.0, front - thickness / 2.0),
+        ))
+        .id();
+    app.update();
+    wall
+}
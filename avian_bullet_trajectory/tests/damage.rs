@@ -0,0 +1,54 @@
+//! Checks the energy and momentum reported at impact, and how damage models turn it into damage.
+
+mod common;
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, DamageModel, EnergyDamage, Hitbox};
+use bevy::prelude::*;
+
+#[test]
+fn impact_energy_and_momentum_match_impact_velocity() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -50.0, 1.0);
+    let mass = 0.0075;
+    let result = common::simulate(
+        &mut app,
+        Vec3::ZERO,
+        Vec3::NEG_Z * 375.0,
+        mass,
+        BulletPhysicsConfig::caliber_9mm(),
+    );
+    assert!(result.hit_entity.is_some(), "should hit the wall");
+
+    let speed = result.impact_velocity.length();
+    let energy = 0.5 * mass * speed * speed;
+    assert!((result.impact_energy - energy).abs() < 1e-3 * energy);
+    assert!(
+        result
+            .impact_momentum
+            .distance(result.impact_velocity * mass)
+            < 1e-6
+    );
+    // Drag has taken some of the muzzle energy on the way
+    assert!(result.impact_energy < 0.5 * mass * 375.0 * 375.0);
+}
+
+#[test]
+fn energy_damage_ignores_weak_hits() {
+    let model = EnergyDamage {
+        damage_per_joule: 0.1,
+        minimum_energy: 80.0,
+    };
+    assert_eq!(model.damage(50.0), 0.0);
+    assert_eq!(model.damage(500.0), 50.0);
+}
+
+#[test]
+fn hitbox_scales_damage() {
+    let model = |energy: f32| energy * 0.2;
+    let head = Hitbox {
+        damage_multiplier: 2.5,
+    };
+    assert_eq!(model.hitbox_damage(100.0, None), 20.0);
+    assert_eq!(model.hitbox_damage(100.0, Some(&Hitbox::default())), 20.0);
+    assert_eq!(model.hitbox_damage(100.0, Some(&head)), 50.0);
+}
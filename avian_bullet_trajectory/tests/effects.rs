@@ -0,0 +1,196 @@
+//! Validates spin drift and Coriolis deflection against published formulas and data.
+
+mod common;
+
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletTrajectoryResult, Coriolis, DragModel, SpinDrift,
+};
+use bevy::prelude::*;
+
+const EARTH_ROTATION: f32 = 7.292_115e-5;
+
+/// .308 Winchester 175gr Sierra MatchKing from a 1:10" barrel at 2600 fps
+const SMK_MASS: f32 = 0.011_34;
+const SMK_SPEED: f32 = 792.5;
+
+/// .50 BMG M33 at 2910 fps
+const BMG_MASS: f32 = 0.042_83;
+const BMG_SPEED: f32 = 887.0;
+
+/// Published drift of the SMK load at 1000 yards in inches: spin drift, and horizontal Coriolis
+/// deflection firing due north at 45° N. Litz's figures for this load, rounded to the inch.
+const SMK_PUBLISHED_DRIFT: (f32, f32) = (13.0, 3.0);
+/// Largest relative difference from the published drift, loose since it is rounded
+const PUBLISHED_TOLERANCE: f32 = 0.2;
+
+fn smk_config() -> BulletPhysicsConfig {
+    BulletPhysicsConfig {
+        drag_model: DragModel::G7 {
+            ballistic_coefficient: 0.243,
+        },
+        ..default()
+    }
+}
+
+fn fire(
+    app: &mut App,
+    speed: f32,
+    mass: f32,
+    config: BulletPhysicsConfig,
+) -> BulletTrajectoryResult {
+    common::simulate(app, Vec3::ZERO, Vec3::NEG_Z * speed, mass, config)
+}
+
+/// `∫ x dt` over the flight, where `x` is the distance downrange
+fn downrange_integral(result: &BulletTrajectoryResult, time_step: f32) -> f32 {
+    result
+        .trajectory_points
+        .windows(2)
+        .map(|segment| -(segment[0].z + segment[1].z) / 2.0 * time_step)
+        .sum()
+}
+
+#[test]
+fn secondary_effects_are_off_by_default() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -500.0, 1.0);
+
+    let result = fire(&mut app, SMK_SPEED, SMK_MASS, smk_config());
+    assert_eq!(result.hit_point.x, 0.0);
+}
+
+#[test]
+fn spin_drift_follows_the_litz_drift_table() {
+    let mut app = common::physics_app();
+    // 1000 yards
+    common::spawn_wall(&mut app, -914.4, 1.0);
+    let spin_drift = SpinDrift::from_twist(0.254, 0.0315, 0.00782, SMK_MASS, SMK_SPEED);
+    // Published Miller stability for this load is about 2.4
+    assert!((spin_drift.stability - 2.4).abs() < 0.05);
+
+    let config = BulletPhysicsConfig {
+        gravity: Vec3::ZERO,
+        spin_drift: Some(spin_drift),
+        ..smk_config()
+    };
+    let result = fire(&mut app, SMK_SPEED, SMK_MASS, config);
+
+    // Right-hand twist drifts right by 1.25 (Sg + 1.2) t^1.83 inches
+    let expected = 0.0254 * 1.25 * (spin_drift.stability + 1.2) * result.time_of_flight.powf(1.83);
+    let drift = result.hit_point.x;
+    assert!(
+        (drift - expected).abs() < expected * 0.02,
+        "drifted {drift} m, expected {expected} m"
+    );
+}
+
+#[test]
+fn smk_drifts_as_published_at_1000_yards() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -914.4, 1.0);
+    let level = fire(&mut app, SMK_SPEED, SMK_MASS, smk_config());
+
+    let spin_drift = SpinDrift::from_twist(0.254, 0.0315, 0.00782, SMK_MASS, SMK_SPEED);
+    let config = BulletPhysicsConfig {
+        spin_drift: Some(spin_drift),
+        ..smk_config()
+    };
+    let drift =
+        (fire(&mut app, SMK_SPEED, SMK_MASS, config).hit_point.x - level.hit_point.x) / 0.0254;
+    let published = SMK_PUBLISHED_DRIFT.0;
+    assert!(
+        (drift - published).abs() < published * PUBLISHED_TOLERANCE,
+        "spin drift of {drift} in instead of {published} in"
+    );
+
+    let config = BulletPhysicsConfig {
+        coriolis: Some(Coriolis::new(FRAC_PI_4, 0.0)),
+        ..smk_config()
+    };
+    let deflection =
+        (fire(&mut app, SMK_SPEED, SMK_MASS, config).hit_point.x - level.hit_point.x) / 0.0254;
+    let published = SMK_PUBLISHED_DRIFT.1;
+    assert!(
+        (deflection - published).abs() < published * PUBLISHED_TOLERANCE,
+        "Coriolis deflection of {deflection} in instead of {published} in"
+    );
+}
+
+#[test]
+fn left_hand_twist_drifts_left() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -500.0, 1.0);
+    let spin_drift = SpinDrift {
+        right_hand_twist: false,
+        ..SpinDrift::from_twist(0.254, 0.0315, 0.00782, SMK_MASS, SMK_SPEED)
+    };
+
+    let config = BulletPhysicsConfig {
+        spin_drift: Some(spin_drift),
+        ..smk_config()
+    };
+    assert!(fire(&mut app, SMK_SPEED, SMK_MASS, config).hit_point.x < 0.0);
+}
+
+#[test]
+fn coriolis_deflects_right_in_the_northern_hemisphere() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -1000.0, 1.0);
+    let latitude = FRAC_PI_4;
+
+    // Firing due north
+    let config = BulletPhysicsConfig {
+        coriolis: Some(Coriolis::new(latitude, 0.0)),
+        ..BulletPhysicsConfig::caliber_50bmg()
+    };
+    let time_step = config.time_step;
+    let result = fire(&mut app, BMG_SPEED, BMG_MASS, config);
+    let (range, time) = (1000.0, result.time_of_flight);
+
+    // With drag also slowing the sideways motion, the deflection is 2 Ω sin(L) (X t - ∫ x dt)
+    let integral = downrange_integral(&result, time_step);
+    let expected = 2.0 * EARTH_ROTATION * latitude.sin() * (range * time - integral);
+    let deflection = result.hit_point.x;
+    assert!(
+        (deflection - expected).abs() < expected * 0.02,
+        "deflected {deflection} m, expected {expected} m"
+    );
+
+    // and within the usual flat fire approximation Ω X t sin(L)
+    let approximation = EARTH_ROTATION * range * time * latitude.sin();
+    assert!((deflection - approximation).abs() < approximation * 0.15);
+}
+
+#[test]
+fn eotvos_effect_lifts_eastward_shots_and_drops_westward_ones() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -1000.0, 1.0);
+    let latitude = FRAC_PI_4;
+    let fire_towards = |app: &mut App, azimuth: Option<f32>| {
+        let config = BulletPhysicsConfig {
+            coriolis: azimuth.map(|azimuth| Coriolis::new(latitude, azimuth)),
+            ..BulletPhysicsConfig::caliber_50bmg()
+        };
+        fire(app, BMG_SPEED, BMG_MASS, config)
+    };
+
+    let level = fire_towards(&mut app, None);
+    let east = fire_towards(&mut app, Some(FRAC_PI_2));
+    let west = fire_towards(&mut app, Some(-FRAC_PI_2));
+
+    let integral = downrange_integral(&level, BulletPhysicsConfig::default().time_step);
+    let expected =
+        2.0 * EARTH_ROTATION * latitude.cos() * (1000.0 * level.time_of_flight - integral);
+    let lift = east.hit_point.y - level.hit_point.y;
+    let drop = level.hit_point.y - west.hit_point.y;
+    assert!(
+        (lift - expected).abs() < expected * 0.05,
+        "lifted {lift} m, expected {expected} m"
+    );
+    assert!(
+        (drop - expected).abs() < expected * 0.05,
+        "dropped {drop} m, expected {expected} m"
+    );
+}
@@ -0,0 +1,131 @@
+//! Checks wind drift, gusts and the standard atmosphere against reference values.
+
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{
+    BallisticEnvironment, BulletPhysicsConfig, ConstantWind, GustingWind, Integrator,
+    SimpleCollider, SimpleCollisionWorld, SimpleShape, StandardAtmosphere, simulate_trajectory,
+};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.0095;
+const MUZZLE_SPEED: f32 = 850.0;
+const RANGE: f32 = 300.0;
+
+/// Fires a 7.62 round level at a wall `RANGE` ahead and returns where and when it hit
+fn fire(environment: Arc<dyn BallisticEnvironment>, launch_time: f32) -> (Vec3, f32) {
+    let mut world = World::new();
+    let world = SimpleCollisionWorld::new([SimpleCollider::new(
+        world.spawn_empty().id(),
+        SimpleShape::Plane {
+            point: Vec3::new(0.0, 0.0, -RANGE),
+            normal: Dir3::Z,
+        },
+    )]);
+    let config = BulletPhysicsConfig {
+        environment,
+        launch_time,
+        integrator: Integrator::Rk4,
+        ..BulletPhysicsConfig::caliber_762()
+    };
+    let result = simulate_trajectory(
+        &world,
+        Vec3::new(0.0, 1.5, 0.0),
+        Vec3::new(0.0, 0.0, -MUZZLE_SPEED),
+        MASS,
+        &config,
+    );
+    assert!(
+        result.hit_entity.is_some(),
+        "the round should reach the wall"
+    );
+    (result.hit_point, result.time_of_flight)
+}
+
+#[test]
+fn crosswind_drift_follows_lag_rule() {
+    let wind = 5.0;
+    let (hit_point, time_of_flight) = fire(
+        Arc::new(ConstantWind {
+            wind: Vec3::new(wind, 0.0, 0.0),
+            ..default()
+        }),
+        0.0,
+    );
+
+    // Didion's lag rule: drift is the wind times the time lost to drag over a vacuum flight
+    let expected = wind * (time_of_flight - RANGE / MUZZLE_SPEED);
+    assert!(hit_point.x > 0.0, "the round should drift downwind");
+    assert!(
+        (hit_point.x - expected).abs() < expected * 0.05,
+        "drifted {} m instead of {expected} m",
+        hit_point.x
+    );
+    assert!((hit_point.z + RANGE).abs() < 1e-3);
+}
+
+#[test]
+fn gusts_repeat_for_the_same_seed_and_time() {
+    let gusts = |seed| GustingWind {
+        base: ConstantWind {
+            wind: Vec3::new(3.0, 0.0, 0.0),
+            ..default()
+        },
+        gust_strength: 4.0,
+        gust_period: 0.2,
+        seed,
+    };
+    let position = Vec3::new(5.0, 1.0, -40.0);
+    for time in [0.0, 0.13, 1.7, 20.5] {
+        assert_eq!(
+            gusts(7).sample(position, time),
+            gusts(7).sample(position, time)
+        );
+    }
+    assert_ne!(
+        gusts(7).sample(position, 0.3),
+        gusts(8).sample(position, 0.3)
+    );
+
+    // The same shot fired again at the same time flies the same, later it meets other gusts
+    let environment: Arc<dyn BallisticEnvironment> = Arc::new(gusts(7));
+    let first = fire(environment.clone(), 0.0);
+    assert_eq!(first, fire(environment.clone(), 0.0));
+    assert_ne!(first.0, fire(environment, 5.0).0);
+}
+
+#[test]
+fn standard_atmosphere_matches_isa_tables() {
+    // Altitude in meters, density in kg/m³ and speed of sound in m/s from the ISA tables
+    let isa = [
+        (0.0, 1.2250, 340.29),
+        (1_000.0, 1.1117, 336.43),
+        (2_000.0, 1.0066, 332.53),
+        (5_000.0, 0.7364, 320.53),
+        (10_000.0, 0.4135, 299.53),
+    ];
+    let atmosphere = StandardAtmosphere::default();
+    for (altitude, density, speed_of_sound) in isa {
+        let air = atmosphere.air_at(altitude);
+        assert!(
+            (air.density - density).abs() < density * 0.002,
+            "{} kg/m³ at {altitude} m instead of {density} kg/m³",
+            air.density
+        );
+        assert!(
+            (air.speed_of_sound - speed_of_sound).abs() < speed_of_sound * 0.002,
+            "{} m/s at {altitude} m instead of {speed_of_sound} m/s",
+            air.speed_of_sound
+        );
+    }
+
+    // The map origin's altitude is added to the height in the world
+    let raised = StandardAtmosphere {
+        ground_altitude: 1_000.0,
+        ..default()
+    };
+    assert_eq!(
+        raised.sample(Vec3::new(0.0, 1_000.0, 0.0), 0.0),
+        atmosphere.air_at(2_000.0)
+    );
+}
@@ -0,0 +1,239 @@
+//! Fires every caliber preset at walls and compares the hits with the checked-in golden file.
+//!
+//! Any change to the integration, drag or collision code that moves a hit shows up here. If the
+//! change is intended, regenerate the file with `UPDATE_GOLDEN=1` and review its diff.
+
+mod common;
+
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletTrajectoryResult, ConstantWind, Integrator,
+};
+use bevy::prelude::*;
+
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+/// Largest difference allowed in the hit point and distance, in meters
+const DISTANCE_TOLERANCE: f32 = 0.001;
+/// Largest difference allowed in the time of flight, in seconds
+const TIME_TOLERANCE: f32 = 0.00001;
+
+struct Scenario {
+    name: String,
+    config: BulletPhysicsConfig,
+    mass: f32,
+    muzzle_speed: f32,
+    wall_distance: f32,
+}
+
+fn scenarios() -> Vec<Scenario> {
+    let presets = [
+        ("9mm", BulletPhysicsConfig::caliber_9mm(), 0.0075, 375.0),
+        ("556", BulletPhysicsConfig::caliber_556(), 0.004, 900.0),
+        ("762", BulletPhysicsConfig::caliber_762(), 0.0095, 850.0),
+        ("50bmg", BulletPhysicsConfig::caliber_50bmg(), 0.0428, 890.0),
+        (
+            "buckshot_00",
+            BulletPhysicsConfig::buckshot_00(),
+            0.0035,
+            400.0,
+        ),
+    ];
+    let mut scenarios = Vec::new();
+    for (name, config, mass, muzzle_speed) in presets {
+        for wall_distance in [25.0, 100.0, 300.0] {
+            scenarios.push(Scenario {
+                name: format!("{name}_{wall_distance}m"),
+                config: config.clone(),
+                mass,
+                muzzle_speed,
+                wall_distance,
+            });
+        }
+    }
+
+    let rifle = |name: &str, config: BulletPhysicsConfig| Scenario {
+        name: name.to_string(),
+        config,
+        mass: 0.004,
+        muzzle_speed: 900.0,
+        wall_distance: 300.0,
+    };
+    scenarios.extend([
+        rifle(
+            "556_300m_semi_implicit_euler",
+            BulletPhysicsConfig {
+                integrator: Integrator::SemiImplicitEuler,
+                ..BulletPhysicsConfig::caliber_556()
+            },
+        ),
+        rifle(
+            "556_300m_rk4",
+            BulletPhysicsConfig {
+                integrator: Integrator::Rk4,
+                ..BulletPhysicsConfig::caliber_556()
+            },
+        ),
+        rifle(
+            "556_300m_adaptive",
+            BulletPhysicsConfig {
+                integrator: Integrator::adaptive(0.0001),
+                ..BulletPhysicsConfig::caliber_556()
+            },
+        ),
+        Scenario {
+            name: "762_300m_crosswind".to_string(),
+            config: BulletPhysicsConfig {
+                environment: Arc::new(ConstantWind {
+                    wind: Vec3::new(5.0, 0.0, 0.0),
+                    ..default()
+                }),
+                ..BulletPhysicsConfig::caliber_762()
+            },
+            mass: 0.0095,
+            muzzle_speed: 850.0,
+            wall_distance: 300.0,
+        },
+        Scenario {
+            name: "50bmg_1000m".to_string(),
+            config: BulletPhysicsConfig::caliber_50bmg(),
+            mass: 0.0428,
+            muzzle_speed: 890.0,
+            wall_distance: 1000.0,
+        },
+    ]);
+    scenarios
+}
+
+/// Fires the scenario's bullet level down -Z at a wall in a fresh app
+fn fire(scenario: &Scenario) -> BulletTrajectoryResult {
+    let mut app = common::physics_app();
+    let wall = common::spawn_wall(&mut app, -scenario.wall_distance, 1.0);
+    let result = common::simulate(
+        &mut app,
+        START,
+        Vec3::NEG_Z * scenario.muzzle_speed,
+        scenario.mass,
+        scenario.config.clone(),
+    );
+    assert_eq!(
+        result.hit_entity,
+        Some(wall),
+        "{} should hit the wall",
+        scenario.name
+    );
+    result
+}
+
+fn golden_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/trajectories.txt")
+}
+
+/// Hit point, distance and time of flight of each scenario in the golden file
+fn read_golden() -> HashMap<String, [f32; 5]> {
+    let golden = fs::read_to_string(golden_path()).expect("golden file should exist");
+    golden
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next().unwrap().to_string();
+            let values = fields
+                .map(|field| field.parse().expect("golden values should be numbers"))
+                .collect::<Vec<f32>>();
+            let values = values
+                .try_into()
+                .expect("golden lines should have 5 values");
+            (name, values)
+        })
+        .collect()
+}
+
+fn write_golden(results: &[(String, [f32; 5])]) {
+    let golden = fs::read_to_string(golden_path()).unwrap_or_default();
+    let mut lines = golden
+        .lines()
+        .take_while(|line| line.starts_with('#'))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    for (name, [x, y, z, distance, time]) in results {
+        lines.push(format!(
+            "{name} {x:.4} {y:.4} {z:.4} {distance:.4} {time:.6}"
+        ));
+    }
+    fs::write(golden_path(), lines.join("\n") + "\n").expect("golden file should be writable");
+}
+
+#[test]
+fn trajectories_match_golden_file() {
+    let results = scenarios()
+        .iter()
+        .map(|scenario| {
+            let result = fire(scenario);
+            let point = result.hit_point;
+            (
+                scenario.name.clone(),
+                [
+                    point.x,
+                    point.y,
+                    point.z,
+                    result.distance,
+                    result.time_of_flight,
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        write_golden(&results);
+        return;
+    }
+
+    let golden = read_golden();
+    let mut mismatches = Vec::new();
+    for (name, actual) in &results {
+        let Some(expected) = golden.get(name) else {
+            mismatches.push(format!("{name}: missing from the golden file"));
+            continue;
+        };
+        let tolerances = [
+            DISTANCE_TOLERANCE,
+            DISTANCE_TOLERANCE,
+            DISTANCE_TOLERANCE,
+            DISTANCE_TOLERANCE,
+            TIME_TOLERANCE,
+        ];
+        let within = actual
+            .iter()
+            .zip(expected)
+            .zip(tolerances)
+            .all(|((actual, expected), tolerance)| (actual - expected).abs() <= tolerance);
+        if !within {
+            mismatches.push(format!("{name}: expected {expected:?}, got {actual:?}"));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "trajectories changed, rerun with UPDATE_GOLDEN=1 if this is intended:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn replays_are_identical() {
+    for scenario in scenarios() {
+        let (first, second) = (fire(&scenario), fire(&scenario));
+        assert_eq!(first.hit_point, second.hit_point, "{}", scenario.name);
+        assert_eq!(first.distance, second.distance, "{}", scenario.name);
+        assert_eq!(
+            first.time_of_flight, second.time_of_flight,
+            "{}",
+            scenario.name
+        );
+        assert_eq!(
+            first.trajectory_points, second.trajectory_points,
+            "{}",
+            scenario.name
+        );
+    }
+}
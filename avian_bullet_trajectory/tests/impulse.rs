@@ -0,0 +1,120 @@
+//! Shoots loose bodies in a headless app and checks they take the bullet's momentum.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BulletImpact, BulletPhysicsConfig, ImpactImpulsePlugin, Projectile, ProjectilePlugin,
+};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+const MASS: f32 = 0.004;
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -900.0);
+
+fn impulse_app() -> App {
+    let mut app = common::physics_app();
+    app.add_plugins((ProjectilePlugin, ImpactImpulsePlugin));
+    app
+}
+
+/// Spawns a floating 1 kg crate centered 20 m down -Z
+fn spawn_crate(app: &mut App, body: RigidBody) -> Entity {
+    let entity = app
+        .world_mut()
+        .spawn((
+            body,
+            Collider::cuboid(1.0, 1.0, 1.0),
+            Mass(1.0),
+            GravityScale(0.0),
+            Transform::from_xyz(0.0, 0.0, -20.0),
+        ))
+        .id();
+    app.update();
+    entity
+}
+
+fn shoot(app: &mut App, start: Vec3) {
+    let result = common::simulate(
+        app,
+        start,
+        VELOCITY,
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+    let impacts = BulletImpact::from_trajectory(&result, MASS);
+    assert_eq!(impacts.len(), 1, "should hit the crate once");
+    assert_eq!(impacts[0].impulse, result.impact_momentum);
+    for impact in impacts {
+        app.world_mut().trigger(impact);
+    }
+    app.update();
+}
+
+#[test]
+fn dynamic_body_takes_bullet_momentum() {
+    let mut app = impulse_app();
+    let body = spawn_crate(&mut app, RigidBody::Dynamic);
+    shoot(&mut app, Vec3::ZERO);
+
+    let velocity = app.world().get::<LinearVelocity>(body).unwrap().0;
+    let expected = MASS * VELOCITY.length();
+    assert!(velocity.z < 0.0, "should be pushed away from the shooter");
+    // Drag takes a little off the bullet over 20 m
+    assert!(
+        (velocity.length() - expected).abs() < 0.05 * expected,
+        "{velocity} m/s"
+    );
+    let spin = app.world().get::<AngularVelocity>(body).unwrap().0;
+    assert!(
+        spin.length() < 1e-3,
+        "a hit through the center shouldn't spin it"
+    );
+}
+
+#[test]
+fn off_center_hit_spins_the_body() {
+    let mut app = impulse_app();
+    let body = spawn_crate(&mut app, RigidBody::Dynamic);
+    shoot(&mut app, Vec3::new(0.0, 0.4, 0.0));
+
+    // Pushing the top away tips the crate backwards, spinning it around -X
+    let spin = app.world().get::<AngularVelocity>(body).unwrap().0;
+    assert!(spin.x < -0.1, "{spin} rad/s");
+}
+
+#[test]
+fn static_body_is_not_moved() {
+    let mut app = impulse_app();
+    let body = spawn_crate(&mut app, RigidBody::Static);
+    shoot(&mut app, Vec3::ZERO);
+
+    let transform = app.world().get::<Transform>(body).unwrap();
+    assert_eq!(transform.translation, Vec3::new(0.0, 0.0, -20.0));
+}
+
+#[test]
+fn projectile_hit_pushes_body() {
+    let mut app = impulse_app();
+    let body = spawn_crate(&mut app, RigidBody::Dynamic);
+    let projectile = app
+        .world_mut()
+        .spawn(Projectile::new(
+            VELOCITY,
+            MASS,
+            BulletPhysicsConfig::caliber_556(),
+        ))
+        .id();
+    for _ in 0..16 {
+        app.update();
+        if app.world().get_entity(projectile).is_err() {
+            break;
+        }
+    }
+    app.update();
+
+    let velocity = app.world().get::<LinearVelocity>(body).unwrap().0;
+    assert!(
+        velocity.z < -0.5 * MASS * VELOCITY.length(),
+        "{velocity} m/s"
+    );
+}
@@ -0,0 +1,153 @@
+//! Compares every integrator against analytic and high-accuracy reference trajectories.
+
+mod common;
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, DragModel, Integrator};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.008;
+const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+
+/// A 9mm-sized bullet with a constant drag coefficient, so the reference solutions stay simple
+fn constant_drag_config(integrator: Integrator, max_time: f32) -> BulletPhysicsConfig {
+    BulletPhysicsConfig {
+        drag_model: DragModel::Constant {
+            drag_coefficient: 0.295,
+        },
+        cross_section_area: 0.0000636,
+        max_time,
+        integrator,
+        ..default()
+    }
+}
+
+/// Drag constant `k` such that the drag deceleration is `k * |v| * v`
+fn drag_constant(config: &BulletPhysicsConfig) -> f32 {
+    let air = config.environment.sample(Vec3::ZERO, 0.0);
+    config.drag_model.deceleration_factor(
+        0.0,
+        air.density,
+        air.speed_of_sound,
+        MASS,
+        config.cross_section_area,
+    )
+}
+
+/// Every integrator with the error we tolerate from it for fixed-step and adaptive modes
+fn integrators(fixed_step: f32, higher_order: f32) -> [(Integrator, f32); 4] {
+    [
+        (Integrator::Euler, fixed_step),
+        (Integrator::SemiImplicitEuler, fixed_step),
+        (Integrator::Rk4, higher_order),
+        (Integrator::adaptive(0.0001), higher_order),
+    ]
+}
+
+/// High-accuracy RK4 in double precision with a 10 µs step
+fn reference_trajectory(start: Vec3, velocity: Vec3, k: f32, duration: f32) -> Vec3 {
+    let gravity = GRAVITY.as_dvec3();
+    let k = k as f64;
+    let acceleration = |v: bevy::math::DVec3| gravity - k * v.length() * v;
+    let (mut p, mut v) = (start.as_dvec3(), velocity.as_dvec3());
+    let (mut time, end) = (0.0, duration as f64);
+    while time < end {
+        let h = (end - time).min(0.00001);
+        let k1 = (v, acceleration(v));
+        let k2 = (v + k1.1 * h / 2.0, acceleration(v + k1.1 * h / 2.0));
+        let k3 = (v + k2.1 * h / 2.0, acceleration(v + k2.1 * h / 2.0));
+        let k4 = (v + k3.1 * h, acceleration(v + k3.1 * h));
+        p += (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0) * h / 6.0;
+        v += (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1) * h / 6.0;
+        time += h;
+    }
+    p.as_vec3()
+}
+
+#[test]
+fn vacuum_trajectories_match_the_analytic_parabola() {
+    let mut app = common::physics_app();
+    let start = Vec3::new(0.0, 1.0, 0.0);
+    let velocity = Vec3::new(0.0, 70.0, -70.0);
+
+    for (integrator, tolerance) in integrators(0.05, 0.01) {
+        let config = BulletPhysicsConfig {
+            drag_model: DragModel::Constant {
+                drag_coefficient: 0.0,
+            },
+            max_time: 2.0,
+            integrator,
+            ..default()
+        };
+        let result = common::simulate(&mut app, start, velocity, MASS, config);
+
+        let t = result.time_of_flight;
+        let expected = start + velocity * t + 0.5 * GRAVITY * t * t;
+        let error = result.hit_point.distance(expected);
+        assert!(
+            error < tolerance,
+            "{integrator:?} is {error} m off the parabola"
+        );
+    }
+}
+
+#[test]
+fn horizontal_drag_matches_the_analytic_solution() {
+    let mut app = common::physics_app();
+    let speed = 375.0;
+
+    for (integrator, tolerance) in integrators(0.25, 0.01) {
+        let config = BulletPhysicsConfig {
+            gravity: Vec3::ZERO,
+            ..constant_drag_config(integrator, 1.0)
+        };
+        let k = drag_constant(&config);
+        let result = common::simulate(&mut app, Vec3::ZERO, Vec3::NEG_Z * speed, MASS, config);
+
+        // With v' = -k v², the distance covered is ln(1 + k v₀ t) / k
+        let expected = (1.0 + k * speed * result.time_of_flight).ln() / k;
+        let error = (-result.hit_point.z - expected).abs();
+        assert!(
+            error < tolerance,
+            "{integrator:?} is {error} m off the analytic drag solution"
+        );
+    }
+}
+
+#[test]
+fn lofted_drag_matches_the_reference_solution() {
+    let mut app = common::physics_app();
+    let start = Vec3::new(0.0, 1.0, 0.0);
+    let velocity = Vec3::new(0.0, 100.0, -300.0);
+
+    for (integrator, tolerance) in integrators(0.5, 0.02) {
+        let config = constant_drag_config(integrator, 3.0);
+        let k = drag_constant(&config);
+        let result = common::simulate(&mut app, start, velocity, MASS, config);
+
+        let expected = reference_trajectory(start, velocity, k, result.time_of_flight);
+        let error = result.hit_point.distance(expected);
+        assert!(
+            error < tolerance,
+            "{integrator:?} is {error} m off the reference drag solution"
+        );
+    }
+}
+
+#[test]
+fn adaptive_integrator_takes_far_fewer_steps() {
+    let mut app = common::physics_app();
+    let velocity = Vec3::new(0.0, 100.0, -300.0);
+    let run = |app: &mut App, integrator| {
+        let config = constant_drag_config(integrator, 3.0);
+        common::simulate(app, Vec3::ZERO, velocity, MASS, config)
+            .trajectory_points
+            .len()
+    };
+
+    let fixed = run(&mut app, Integrator::Euler);
+    let adaptive = run(&mut app, Integrator::adaptive(0.0001));
+    assert!(
+        adaptive * 10 < fixed,
+        "adaptive took {adaptive} steps, fixed took {fixed}"
+    );
+}
@@ -0,0 +1,84 @@
+//! Fires through and off walls of different materials and checks each hit reports its material.
+
+mod common;
+
+use avian_bullet_trajectory::{BallisticMaterial, BulletPhysicsConfig, ImpactEffect};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.004;
+
+#[test]
+fn reports_material_of_each_hit() {
+    let mut app = common::physics_app();
+    let board = common::spawn_wall(&mut app, -10.0, 0.02);
+    let plate = common::spawn_wall(&mut app, -20.0, 1.0);
+    app.world_mut()
+        .entity_mut(board)
+        .insert(BallisticMaterial::plywood());
+    app.world_mut()
+        .entity_mut(plate)
+        .insert(BallisticMaterial::steel());
+
+    let result = common::simulate_with_materials(
+        &mut app,
+        Vec3::ZERO,
+        Vec3::NEG_Z * 900.0,
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+
+    assert_eq!(
+        result.penetrations.len(),
+        1,
+        "should shoot through the board"
+    );
+    assert_eq!(result.penetrations[0].entity, board);
+    assert_eq!(
+        result.penetrations[0].material,
+        BallisticMaterial::plywood()
+    );
+    assert_eq!(result.hit_entity, Some(plate), "should stop in the plate");
+    let material = result.hit_material.expect("plate has a material");
+    assert_eq!(material.impact_effect, ImpactEffect::Sparks);
+}
+
+#[test]
+fn reports_material_of_ricochets() {
+    let mut app = common::physics_app();
+    let plate = common::spawn_wall(&mut app, -5.0, 1.0);
+    app.world_mut()
+        .entity_mut(plate)
+        .insert(BallisticMaterial::steel());
+
+    // Skim the plate at about 6°
+    let result = common::simulate_with_materials(
+        &mut app,
+        Vec3::ZERO,
+        Vec3::new(900.0, 0.0, -100.0),
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+
+    assert_eq!(result.ricochets.len(), 1, "should glance off the plate");
+    assert_eq!(result.ricochets[0].entity, plate);
+    assert_eq!(result.ricochets[0].material, BallisticMaterial::steel());
+    assert!(result.hit_entity.is_none());
+    assert!(result.hit_material.is_none());
+}
+
+#[test]
+fn colliders_without_material_report_none() {
+    let mut app = common::physics_app();
+    let wall = common::spawn_wall(&mut app, -10.0, 1.0);
+
+    let result = common::simulate_with_materials(
+        &mut app,
+        Vec3::ZERO,
+        Vec3::NEG_Z * 900.0,
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+
+    assert_eq!(result.hit_entity, Some(wall));
+    assert!(result.hit_material.is_none());
+}
@@ -0,0 +1,170 @@
+//! Fires through boards and walls and checks what bullets pass through and what stops them.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BallisticMaterial, BallisticMaterials, BulletPhysicsConfig, BulletSpatialQuery,
+    BulletTrajectory, BulletTrajectoryResult, DragModel,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+const MASS: f32 = 0.004;
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -900.0);
+
+/// A static wall facing the bullet, with its front face at `z = front`
+fn wall(front: f32, thickness: f32) -> impl Bundle {
+    (
+        RigidBody::Static,
+        Collider::cuboid(100.0, 100.0, thickness),
+        Transform::from_xyz(0.0, 0.0, front - thickness / 2.0),
+    )
+}
+
+/// Fires once with the colliders' materials looked up, after letting the spatial query pipeline
+/// pick up what was spawned
+fn fire(app: &mut App, config: BulletPhysicsConfig) -> BulletTrajectoryResult {
+    app.update();
+    app.world_mut()
+        .run_system_once(
+            move |spatial_query: BulletSpatialQuery, materials: BallisticMaterials| {
+                spatial_query.simulate_bullet_with_materials(
+                    START,
+                    VELOCITY,
+                    MASS,
+                    Some(config.clone()),
+                    &SpatialQueryFilter::default(),
+                    &materials,
+                )
+            },
+        )
+        .expect("trajectory system should run")
+}
+
+#[test]
+fn passes_through_board_and_hits_wall_behind() {
+    let mut app = common::physics_app();
+    let world = app.world_mut();
+    let board = world
+        .spawn((wall(-10.0, 0.02), BallisticMaterial::plywood()))
+        .id();
+    let backstop = world.spawn(wall(-30.0, 1.0)).id();
+    let result = fire(&mut app, BulletPhysicsConfig::caliber_556());
+
+    assert_eq!(result.penetrations.len(), 1);
+    let penetration = &result.penetrations[0];
+    assert_eq!(penetration.entity, board);
+    assert!((penetration.entry_point.z + 10.0).abs() < 1e-3);
+    assert!((penetration.exit_point.z + 10.02).abs() < 1e-3);
+    assert_eq!(result.hit_entity, Some(backstop));
+    assert!((result.hit_point.z + 30.0).abs() < 1e-3);
+}
+
+#[test]
+fn exit_speed_matches_energy_lost_in_material() {
+    let mut app = common::physics_app();
+    let material = BallisticMaterial::plywood();
+    let thickness = 0.1;
+    app.world_mut().spawn((wall(-10.0, thickness), material));
+    // Without gravity or drag the bullet reaches the board as fast as it left the muzzle
+    let config = BulletPhysicsConfig {
+        gravity: Vec3::ZERO,
+        drag_model: DragModel::Constant {
+            drag_coefficient: 0.0,
+        },
+        max_distance: 50.0,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = fire(&mut app, config);
+
+    assert_eq!(result.penetrations.len(), 1);
+    let penetration = &result.penetrations[0];
+    let entry_energy = 0.5 * MASS * VELOCITY.length_squared();
+    let exit_energy = entry_energy - material.penetration_resistance * thickness;
+    let exit_speed = (2.0 * exit_energy / MASS).sqrt();
+    assert!(
+        (penetration.exit_velocity.length() - exit_speed).abs() < 0.5,
+        "left at {} m/s instead of {exit_speed} m/s",
+        penetration.exit_velocity.length()
+    );
+    // Passing through doesn't turn the bullet
+    assert!(
+        penetration
+            .exit_velocity
+            .normalize()
+            .dot(VELOCITY.normalize())
+            > 0.9999
+    );
+}
+
+#[test]
+fn stops_in_collider_thicker_than_max_depth() {
+    let mut app = common::physics_app();
+    // Soft enough that the bullet would make it through if it were thin enough
+    let material = BallisticMaterial {
+        penetration_resistance: 1.0,
+        ..BallisticMaterial::plywood()
+    };
+    let block = app.world_mut().spawn((wall(-10.0, 2.0), material)).id();
+    let config = BulletPhysicsConfig {
+        max_penetration_depth: 1.0,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = fire(&mut app, config);
+
+    assert!(result.penetrations.is_empty());
+    assert_eq!(result.hit_entity, Some(block));
+    assert!((result.hit_point.z + 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn does_not_hit_collider_it_passed_through_again() {
+    let mut app = common::physics_app();
+    let world = app.world_mut();
+    // Two boards of the same collider, the second of them too thick to pass through
+    let fence = world
+        .spawn((
+            RigidBody::Static,
+            Collider::compound(vec![
+                (
+                    Vec3::new(0.0, 0.0, -10.01),
+                    Quat::IDENTITY,
+                    Collider::cuboid(100.0, 100.0, 0.02),
+                ),
+                (
+                    Vec3::new(0.0, 0.0, -21.0),
+                    Quat::IDENTITY,
+                    Collider::cuboid(100.0, 100.0, 2.0),
+                ),
+            ]),
+            BallisticMaterial::plywood(),
+        ))
+        .id();
+    let backstop = world.spawn(wall(-40.0, 1.0)).id();
+    let result = fire(&mut app, BulletPhysicsConfig::caliber_556());
+
+    assert_eq!(result.penetrations.len(), 1);
+    assert_eq!(result.penetrations[0].entity, fence);
+    assert_eq!(result.hit_entity, Some(backstop));
+}
+
+#[test]
+fn materials_are_ignored_without_a_lookup() {
+    let mut app = common::physics_app();
+    let board = app
+        .world_mut()
+        .spawn((wall(-10.0, 0.02), BallisticMaterial::plywood()))
+        .id();
+    app.update();
+    let result = common::simulate(
+        &mut app,
+        START,
+        VELOCITY,
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+
+    assert!(result.penetrations.is_empty());
+    assert_eq!(result.hit_entity, Some(board));
+}
@@ -0,0 +1,117 @@
+//! Runs projectiles tick by tick in a headless app and compares them with instant simulation.
+
+mod common;
+
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, GustingWind, Projectile, ProjectileHit, ProjectilePlugin,
+    StandardAtmosphere,
+};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.004;
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+const VELOCITY: Vec3 = Vec3::new(0.0, 2.0, -940.0);
+
+#[derive(Resource, Default)]
+struct Hits(Vec<ProjectileHit>);
+
+fn projectile_app() -> App {
+    let mut app = common::physics_app();
+    app.add_plugins(ProjectilePlugin)
+        .init_resource::<Hits>()
+        .add_observer(|hit: On<ProjectileHit>, mut hits: ResMut<Hits>| {
+            hits.0.push(hit.clone());
+        });
+    app
+}
+
+/// Fires a projectile and runs the app until it is gone, returning its hits
+fn fire(app: &mut App, config: BulletPhysicsConfig) -> Vec<ProjectileHit> {
+    let projectile = app
+        .world_mut()
+        .spawn((
+            Projectile::new(VELOCITY, MASS, config),
+            Transform::from_translation(START),
+        ))
+        .id();
+    for _ in 0..256 {
+        app.update();
+        if app.world().get_entity(projectile).is_err() {
+            break;
+        }
+    }
+    assert!(
+        app.world().get_entity(projectile).is_err(),
+        "projectile should be despawned"
+    );
+    std::mem::take(&mut app.world_mut().resource_mut::<Hits>().0)
+}
+
+fn gusting_config() -> BulletPhysicsConfig {
+    BulletPhysicsConfig {
+        environment: Arc::new(GustingWind {
+            base: StandardAtmosphere {
+                wind: Vec3::new(6.0, 0.0, 0.0),
+                ..default()
+            },
+            gust_strength: 4.0,
+            gust_period: 0.1,
+            seed: 7,
+        }),
+        ..BulletPhysicsConfig::caliber_556()
+    }
+}
+
+#[test]
+fn projectile_hits_the_wall_and_despawns() {
+    let mut app = projectile_app();
+    let wall = common::spawn_wall(&mut app, -300.0, 1.0);
+
+    let hits = fire(&mut app, BulletPhysicsConfig::caliber_556());
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].hit_entity, wall);
+    assert!((hits[0].point.z + 300.0).abs() < 0.001);
+    assert!(hits[0].time_of_flight > 0.3 && hits[0].time_of_flight < 0.4);
+}
+
+#[test]
+fn projectile_without_a_target_expires() {
+    let mut app = projectile_app();
+    let config = BulletPhysicsConfig {
+        max_time: 0.5,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+
+    assert!(fire(&mut app, config).is_empty());
+}
+
+#[test]
+fn projectile_runs_are_deterministic() {
+    let mut app = projectile_app();
+    common::spawn_wall(&mut app, -300.0, 1.0);
+
+    let first = fire(&mut app, gusting_config());
+    let second = fire(&mut app, gusting_config());
+    assert_eq!(first[0].point, second[0].point);
+    assert_eq!(first[0].velocity, second[0].velocity);
+    assert_eq!(first[0].time_of_flight, second[0].time_of_flight);
+}
+
+#[test]
+fn projectile_matches_instant_simulation() {
+    let mut app = projectile_app();
+    common::spawn_wall(&mut app, -300.0, 1.0);
+
+    let hits = fire(&mut app, gusting_config());
+    let instant = common::simulate(&mut app, START, VELOCITY, MASS, gusting_config());
+
+    // Ticks cut a few steps short, so the two only agree to within integration error
+    let error = hits[0].point.distance(instant.hit_point);
+    assert!(
+        error < 0.01,
+        "projectile landed {error} m from the instant simulation"
+    );
+    assert!((hits[0].time_of_flight - instant.time_of_flight).abs() < 0.001);
+}
@@ -0,0 +1,88 @@
+//! Checks the drag tables and the rifle presets against published ballistics data.
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, DragModel, Integrator, Zeroing};
+
+#[test]
+fn drag_tables_match_standard_rows() {
+    let g1 = DragModel::G1 {
+        ballistic_coefficient: 1.0,
+    };
+    let g7 = DragModel::G7 {
+        ballistic_coefficient: 1.0,
+    };
+    // Rows of McCoy's G1 and G7 tables, across subsonic, transonic and supersonic flight
+    for (model, mach, drag_coefficient) in [
+        (g1, 0.5, 0.2032),
+        (g1, 1.0, 0.4805),
+        (g1, 1.4, 0.6625),
+        (g1, 3.0, 0.5133),
+        (g7, 0.5, 0.1194),
+        (g7, 1.0, 0.3803),
+        (g7, 1.05, 0.4043),
+        (g7, 3.0, 0.2424),
+    ] {
+        let actual = model.drag_coefficient(mach);
+        assert!(
+            (actual - drag_coefficient).abs() < 1e-4,
+            "{model:?} at Mach {mach}: {actual} instead of {drag_coefficient}"
+        );
+    }
+}
+
+/// Range in meters, drop below the line of sight in meters and time of flight in seconds
+type ChartRow = (f32, f32, f32);
+
+/// Drop and time of flight at 100, 300 and 500 m, for a 100 m zero with the sights 38 mm above
+/// the bore, in still ICAO sea level air
+///
+/// Rounded to the precision of published drop charts for M855 (62 gr, 920 m/s) and M80
+/// (147 gr, 838 m/s), so the tolerances are loose.
+const PUBLISHED: [(&str, [ChartRow; 3]); 2] = [
+    (
+        "556",
+        [(100.0, 0.0, 0.115), (300.0, 0.42, 0.40), (500.0, 1.9, 0.77)],
+    ),
+    (
+        "762",
+        [(100.0, 0.0, 0.125), (300.0, 0.47, 0.42), (500.0, 2.0, 0.78)],
+    ),
+];
+/// Largest relative difference in drop, and absolute in meters near the zero
+const DROP_TOLERANCE: (f32, f32) = (0.15, 0.02);
+/// Largest relative difference in time of flight
+const TIME_TOLERANCE: f32 = 0.04;
+
+#[test]
+fn rifle_presets_match_published_drop_and_time_of_flight() {
+    for (name, published) in PUBLISHED {
+        let (config, mass, muzzle_speed) = match name {
+            "556" => (BulletPhysicsConfig::caliber_556(), 0.004_018, 920.0),
+            _ => (BulletPhysicsConfig::caliber_762(), 0.009_525, 838.0),
+        };
+        let config = BulletPhysicsConfig {
+            integrator: Integrator::Rk4,
+            ..config
+        };
+        let zeroing = Zeroing::new(0.038, 100.0);
+        let card = config
+            .range_card(mass, muzzle_speed, 100.0, 500.0, Some(&zeroing))
+            .expect("the zero should be in reach");
+        for (range, drop, time_of_flight) in published {
+            let row = card
+                .rows
+                .iter()
+                .find(|row| row.range == range)
+                .expect("the card should have a row for every published range");
+            assert!(
+                (row.drop - drop).abs() <= (drop * DROP_TOLERANCE.0).max(DROP_TOLERANCE.1),
+                "{name} at {range} m drops {} m instead of {drop} m",
+                row.drop
+            );
+            assert!(
+                (row.time_of_flight - time_of_flight).abs() <= time_of_flight * TIME_TOLERANCE,
+                "{name} at {range} m takes {} s instead of {time_of_flight} s",
+                row.time_of_flight
+            );
+        }
+    }
+}
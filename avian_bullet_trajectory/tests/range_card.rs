@@ -0,0 +1,100 @@
+//! Builds range cards and checks them against vacuum physics and the zeroing.
+
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, ConstantWind, DragModel, Integrator, Zeroing};
+use bevy::prelude::*;
+
+#[test]
+fn vacuum_card_matches_free_fall() {
+    let config = BulletPhysicsConfig {
+        drag_model: DragModel::Constant {
+            drag_coefficient: 0.0,
+        },
+        integrator: Integrator::Rk4,
+        ..default()
+    };
+    let card = config
+        .range_card(0.004, 500.0, 50.0, 400.0, None)
+        .expect("cards without a zero always build");
+    assert_eq!(card.rows.len(), 8);
+    for (index, row) in card.rows.iter().enumerate() {
+        let range = 50.0 * (index + 1) as f32;
+        let time = range / 500.0;
+        assert_eq!(row.range, range);
+        assert!((row.time_of_flight - time).abs() < 1e-4, "{row:?}");
+        assert!(
+            (row.drop - 0.5 * 9.81 * time * time).abs() < 1e-3,
+            "{row:?}"
+        );
+        assert!(row.drift.abs() < 1e-6, "{row:?}");
+        assert!((row.drop_angle - row.drop.atan2(range)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn zeroed_card_has_no_drop_at_the_zero() {
+    let config = BulletPhysicsConfig::caliber_556();
+    let zeroing = Zeroing::new(0.07, 100.0);
+    let card = config
+        .range_card(0.004, 900.0, 100.0, 500.0, Some(&zeroing))
+        .expect("the zero should be in reach");
+    assert_eq!(card.rows.len(), 5);
+    assert!(card.rows[0].drop.abs() < 0.002, "{:?}", card.rows[0]);
+    // Drag makes the bullet drop faster and faster
+    for pair in card.rows.windows(2).skip(1) {
+        assert!(pair[1].drop > pair[0].drop);
+        assert!(pair[1].speed < pair[0].speed);
+        assert!(pair[1].energy < pair[0].energy);
+    }
+}
+
+#[test]
+fn unreachable_zero_has_no_card() {
+    // A pistol round zeroed further out than it can carry
+    let zeroing = Zeroing::new(0.02, 5000.0);
+    let card =
+        BulletPhysicsConfig::caliber_9mm().range_card(0.0075, 375.0, 25.0, 100.0, Some(&zeroing));
+    assert!(card.is_none());
+}
+
+#[test]
+fn crosswind_drifts_bullet() {
+    let config = BulletPhysicsConfig {
+        environment: Arc::new(ConstantWind {
+            wind: Vec3::new(5.0, 0.0, 0.0),
+            ..default()
+        }),
+        ..BulletPhysicsConfig::caliber_762()
+    };
+    let card = config
+        .range_card(0.0095, 850.0, 100.0, 300.0, None)
+        .expect("cards without a zero always build");
+    assert_eq!(card.rows.len(), 3);
+    assert!(card.rows.iter().all(|row| row.drift > 0.0));
+    assert!(card.rows[2].drift > card.rows[0].drift);
+}
+
+#[test]
+fn card_exports_csv_and_json() {
+    let card = BulletPhysicsConfig::caliber_9mm()
+        .range_card(0.0075, 375.0, 25.0, 100.0, None)
+        .expect("cards without a zero always build");
+    let csv = card.to_csv();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("range_m,drop_m"));
+    assert_eq!(lines.count(), card.rows.len());
+
+    let json: serde_json::Value = serde_json::from_str(&card.to_json()).unwrap();
+    let rows = json
+        .as_array()
+        .expect("the card should be an array of rows");
+    assert_eq!(rows.len(), card.rows.len());
+    // Numbers come back exactly as written
+    let number = |json: &serde_json::Value| json.as_f64().map(|number| number as f32);
+    for (json, row) in rows.iter().zip(&card.rows) {
+        assert_eq!(number(&json["range"]), Some(row.range));
+        assert_eq!(number(&json["drop"]), Some(row.drop));
+        assert_eq!(number(&json["time_of_flight"]), Some(row.time_of_flight));
+    }
+}
@@ -0,0 +1,147 @@
+//! Checks that both simulation functions record the path according to the configured policy.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletSpatialQuery, BulletTrajectory, TrajectoryRecording,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+const VELOCITY: Vec3 = Vec3::new(0.0, 50.0, -300.0);
+
+/// Simulates one second of flight in empty space with the given policy
+fn points(app: &mut App, recording: TrajectoryRecording, gravity: Vec3) -> Vec<Vec3> {
+    let config = BulletPhysicsConfig {
+        gravity,
+        max_time: 1.0,
+        recording,
+        ..default()
+    };
+    common::simulate(app, Vec3::ZERO, VELOCITY, 0.008, config).trajectory_points
+}
+
+#[test]
+fn recording_can_be_turned_off() {
+    let mut app = common::physics_app();
+    let gravity = Vec3::NEG_Y * 9.81;
+    assert!(points(&mut app, TrajectoryRecording::None, gravity).is_empty());
+}
+
+#[test]
+fn step_and_interval_policies_thin_out_the_path() {
+    let mut app = common::physics_app();
+    let gravity = Vec3::NEG_Y * 9.81;
+
+    let every_step = points(&mut app, TrajectoryRecording::EveryNSteps(1), gravity).len();
+    let every_tenth = points(&mut app, TrajectoryRecording::EveryNSteps(10), gravity).len();
+    let interval = points(&mut app, TrajectoryRecording::Interval(0.1), gravity).len();
+
+    // 1000 steps of 1 ms, plus the start point
+    assert!((1000..=1002).contains(&every_step), "{every_step} points");
+    assert!((100..=102).contains(&every_tenth), "{every_tenth} points");
+    assert!((10..=12).contains(&interval), "{interval} points");
+}
+
+#[test]
+fn simplification_keeps_the_path_within_tolerance() {
+    let mut app = common::physics_app();
+    let gravity = Vec3::NEG_Y * 9.81;
+    let tolerance = 0.01;
+
+    let full = points(&mut app, TrajectoryRecording::EveryNSteps(1), gravity);
+    let simplified = points(
+        &mut app,
+        TrajectoryRecording::Simplified { tolerance },
+        gravity,
+    );
+    assert!(simplified.len() * 10 < full.len());
+    assert_eq!(simplified.first(), full.first());
+    assert_eq!(simplified.last(), full.last());
+    for point in full {
+        let distance = distance_to_path(&simplified, point);
+        assert!(distance <= tolerance + 1e-4, "{point} is {distance} m off");
+    }
+
+    // Without gravity or drag, the path is a straight line
+    let straight = BulletPhysicsConfig {
+        gravity: Vec3::ZERO,
+        drag_model: avian_bullet_trajectory::DragModel::Constant {
+            drag_coefficient: 0.0,
+        },
+        max_time: 1.0,
+        recording: TrajectoryRecording::Simplified { tolerance },
+        ..default()
+    };
+    let line = common::simulate(&mut app, Vec3::ZERO, VELOCITY, 0.008, straight);
+    assert_eq!(line.trajectory_points.len(), 2);
+}
+
+#[test]
+fn simplification_holds_across_windows() {
+    let mut app = common::physics_app();
+    let tolerance = 0.01;
+    let config = |recording| BulletPhysicsConfig {
+        max_time: 5.0,
+        recording,
+        ..default()
+    };
+
+    // Long enough to be simplified in several windows as it flies
+    let full = common::simulate(
+        &mut app,
+        Vec3::ZERO,
+        VELOCITY,
+        0.008,
+        config(TrajectoryRecording::EveryNSteps(1)),
+    )
+    .trajectory_points;
+    let simplified = common::simulate(
+        &mut app,
+        Vec3::ZERO,
+        VELOCITY,
+        0.008,
+        config(TrajectoryRecording::Simplified { tolerance }),
+    )
+    .trajectory_points;
+    assert!(full.len() > 4000, "{} points", full.len());
+    assert!(simplified.len() * 10 < full.len());
+    assert_eq!(simplified.last(), full.last());
+    for point in full {
+        let distance = distance_to_path(&simplified, point);
+        assert!(distance <= tolerance + 1e-4, "{point} is {distance} m off");
+    }
+}
+
+/// Distance from `point` to the closest segment of `path`
+fn distance_to_path(path: &[Vec3], point: Vec3) -> f32 {
+    path.windows(2)
+        .map(|segment| {
+            let line = segment[1] - segment[0];
+            let t = ((point - segment[0]).dot(line) / line.length_squared()).clamp(0.0, 1.0);
+            point.distance(segment[0] + line * t)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[test]
+fn simple_trajectory_uses_the_same_policy() {
+    let mut app = common::physics_app();
+    let points = app
+        .world_mut()
+        .run_system_once(|spatial_query: BulletSpatialQuery| {
+            spatial_query
+                .simulate_simple_trajectory_with_recording(
+                    Vec3::ZERO,
+                    VELOCITY,
+                    None,
+                    TrajectoryRecording::Interval(1.0),
+                    &SpatialQueryFilter::default(),
+                )
+                .trajectory_points
+        })
+        .expect("trajectory system should run");
+
+    // 30 s of flight at 10 ms steps, recorded once a second
+    assert!((30..=32).contains(&points.len()), "{} points", points.len());
+}
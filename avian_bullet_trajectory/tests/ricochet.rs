@@ -0,0 +1,146 @@
+//! Fires at steel plates at different angles and checks which hits bounce off.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BallisticMaterial, BallisticMaterials, BulletPhysicsConfig, BulletSpatialQuery,
+    BulletTrajectory, BulletTrajectoryResult,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+const MASS: f32 = 0.004;
+const START: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -900.0);
+
+/// A plate 10 m ahead, tilted back from facing the bullet by `tilt` radians
+fn plate(tilt: f32) -> impl Bundle {
+    (
+        RigidBody::Static,
+        Collider::cuboid(20.0, 20.0, 0.05),
+        Transform::from_xyz(0.0, 1.5, -10.0).with_rotation(Quat::from_rotation_x(-tilt)),
+    )
+}
+
+/// Fires once with the colliders' materials looked up, after letting the spatial query pipeline
+/// pick up what was spawned
+fn fire(app: &mut App, velocity: Vec3, config: BulletPhysicsConfig) -> BulletTrajectoryResult {
+    app.update();
+    app.world_mut()
+        .run_system_once(
+            move |spatial_query: BulletSpatialQuery, materials: BallisticMaterials| {
+                spatial_query.simulate_bullet_with_materials(
+                    START,
+                    velocity,
+                    MASS,
+                    Some(config.clone()),
+                    &SpatialQueryFilter::default(),
+                    &materials,
+                )
+            },
+        )
+        .expect("trajectory system should run")
+}
+
+#[test]
+fn grazing_hit_bounces() {
+    let mut app = common::physics_app();
+    // Hit 10° from the surface, well under steel's ricochet angle
+    let steel = app
+        .world_mut()
+        .spawn((plate(80f32.to_radians()), BallisticMaterial::steel()))
+        .id();
+    let config = BulletPhysicsConfig {
+        max_distance: 50.0,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = fire(&mut app, VELOCITY, config);
+
+    assert_eq!(result.ricochets.len(), 1);
+    let ricochet = &result.ricochets[0];
+    assert_eq!(ricochet.entity, steel);
+    // Bounced up and away from the plate, slower than it came in
+    assert!(ricochet.outgoing_velocity.dot(ricochet.normal) > 0.0);
+    assert!(ricochet.outgoing_velocity.y > 0.0);
+    assert!(ricochet.outgoing_velocity.length() < ricochet.incoming_velocity.length());
+    assert!(result.hit_entity.is_none());
+}
+
+#[test]
+fn steep_hit_does_not_bounce() {
+    let mut app = common::physics_app();
+    // Hit 60° from the surface, over steel's ricochet angle
+    let steel = app
+        .world_mut()
+        .spawn((plate(30f32.to_radians()), BallisticMaterial::steel()))
+        .id();
+    let result = fire(&mut app, VELOCITY, BulletPhysicsConfig::caliber_556());
+
+    assert!(result.ricochets.is_empty());
+    assert_eq!(result.hit_entity, Some(steel));
+}
+
+#[test]
+fn stops_bouncing_after_max_ricochets() {
+    let mut app = common::physics_app();
+    // A perfectly bouncy corridor the bullet skims along, bouncing between floor and ceiling
+    let bouncy = BallisticMaterial {
+        restitution: 1.0,
+        friction: 0.0,
+        ..BallisticMaterial::steel()
+    };
+    let world = app.world_mut();
+    for height in [-0.5, 3.5] {
+        world.spawn((
+            RigidBody::Static,
+            Collider::cuboid(100.0, 1.0, 2000.0),
+            Transform::from_xyz(0.0, height, -1000.0),
+            bouncy,
+        ));
+    }
+    let config = BulletPhysicsConfig {
+        max_ricochets: 3,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = fire(&mut app, Vec3::new(0.0, -30.0, -900.0), config);
+
+    assert_eq!(result.ricochets.len(), 3);
+    assert!(result.hit_entity.is_some());
+}
+
+#[test]
+fn hit_without_normal_does_not_bounce() {
+    let mut app = common::physics_app();
+    // Starting inside a steel block, the ray reports a hit with no normal to bounce off
+    let block = app
+        .world_mut()
+        .spawn((
+            RigidBody::Static,
+            Collider::cuboid(2.0, 3.0, 2.0),
+            Transform::from_xyz(0.0, 1.5, 0.0),
+            BallisticMaterial::steel(),
+        ))
+        .id();
+    let result = fire(&mut app, VELOCITY, BulletPhysicsConfig::caliber_556());
+
+    assert!(result.ricochets.is_empty());
+    assert_eq!(result.hit_entity, Some(block));
+    assert_eq!(result.hit_point, START);
+}
+
+#[test]
+fn colliders_without_material_do_not_bounce() {
+    let mut app = common::physics_app();
+    let plate = app.world_mut().spawn(plate(80f32.to_radians())).id();
+    app.update();
+    let result = common::simulate(
+        &mut app,
+        START,
+        VELOCITY,
+        MASS,
+        BulletPhysicsConfig::caliber_556(),
+    );
+
+    assert!(result.ricochets.is_empty());
+    assert_eq!(result.hit_entity, Some(plate));
+}
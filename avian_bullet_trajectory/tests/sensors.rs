@@ -0,0 +1,85 @@
+//! Fires through trigger volumes and checks bullets only stop on them when asked to.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletSpatialQuery, CollisionWorld, Projectile, ProjectileHit,
+    ProjectilePlugin, SpatialQueryWorld,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+const MASS: f32 = 0.004;
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -900.0);
+
+/// A sensor volume 10 m out with a solid wall behind it
+fn sensor_app() -> (App, Entity, Entity) {
+    let mut app = common::physics_app();
+    let sensor = common::spawn_wall(&mut app, -10.0, 1.0);
+    app.world_mut().entity_mut(sensor).insert(Sensor);
+    let wall = common::spawn_wall(&mut app, -20.0, 1.0);
+    (app, sensor, wall)
+}
+
+#[test]
+fn bullets_fly_through_sensors() {
+    let (mut app, _, wall) = sensor_app();
+    let config = BulletPhysicsConfig::caliber_556();
+    let result = common::simulate_with_materials(&mut app, Vec3::ZERO, VELOCITY, MASS, config);
+    assert_eq!(result.hit_entity, Some(wall));
+    assert!(result.penetrations.is_empty());
+}
+
+#[test]
+fn bullets_fly_through_sensors_without_materials() {
+    let (mut app, _, wall) = sensor_app();
+    let config = BulletPhysicsConfig::caliber_556();
+    let result = common::simulate(&mut app, Vec3::ZERO, VELOCITY, MASS, config);
+    assert_eq!(result.hit_entity, Some(wall));
+}
+
+#[test]
+fn spatial_query_world_finds_sensors_without_materials() {
+    let (mut app, sensor, wall) = sensor_app();
+    let sensors = app
+        .world_mut()
+        .run_system_once(move |spatial_query: BulletSpatialQuery| {
+            let filter = SpatialQueryFilter::default();
+            let world = SpatialQueryWorld::new(&spatial_query, &filter);
+            (world.is_sensor(sensor), world.is_sensor(wall))
+        })
+        .expect("lookup system should run");
+    assert_eq!(sensors, (true, false));
+}
+
+#[test]
+fn bullets_stop_on_sensors_when_not_skipped() {
+    let (mut app, sensor, _) = sensor_app();
+    let config = BulletPhysicsConfig {
+        skip_sensors: false,
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let result = common::simulate_with_materials(&mut app, Vec3::ZERO, VELOCITY, MASS, config);
+    assert_eq!(result.hit_entity, Some(sensor));
+}
+
+#[test]
+fn projectiles_fly_through_sensors() {
+    let (mut app, _, wall) = sensor_app();
+    app.add_plugins(ProjectilePlugin)
+        .add_observer(move |hit: On<ProjectileHit>| {
+            assert_eq!(hit.hit_entity, wall, "should only hit the wall");
+        });
+    let projectile = app
+        .world_mut()
+        .spawn(Projectile::new(
+            VELOCITY,
+            MASS,
+            BulletPhysicsConfig::caliber_556(),
+        ))
+        .id();
+    for _ in 0..16 {
+        app.update();
+    }
+    assert!(app.world().get_entity(projectile).is_err());
+}
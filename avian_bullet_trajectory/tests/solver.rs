@@ -0,0 +1,115 @@
+//! Checks launch solutions against the analytic vacuum solution and by firing them at a wall.
+
+mod common;
+
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, ConstantWind, DragModel};
+use bevy::prelude::*;
+
+const MASS: f32 = 0.004;
+
+#[test]
+fn vacuum_solutions_match_the_analytic_angles() {
+    let config = BulletPhysicsConfig {
+        drag_model: DragModel::Constant {
+            drag_coefficient: 0.0,
+        },
+        ..default()
+    };
+    let (speed, range, height) = (100.0_f32, 500.0_f32, 20.0_f32);
+    let g = 9.81;
+
+    let solutions = config.solve_launch(Vec3::ZERO, speed, MASS, Vec3::new(0.0, height, -range));
+
+    // tan θ = (v² ± √(v⁴ - g(g x² + 2 y v²))) / (g x)
+    let root = (speed.powi(4) - g * (g * range * range + 2.0 * height * speed * speed)).sqrt();
+    let low = ((speed * speed - root) / (g * range)).atan();
+    let high = ((speed * speed + root) / (g * range)).atan();
+    let solved_low = solutions.low.expect("low arc should exist").elevation;
+    let solved_high = solutions.high.expect("high arc should exist").elevation;
+    assert!((solved_low - low).abs() < 0.001, "{solved_low} vs {low}");
+    assert!(
+        (solved_high - high).abs() < 0.001,
+        "{solved_high} vs {high}"
+    );
+}
+
+#[test]
+fn out_of_range_targets_have_no_solution() {
+    let config = BulletPhysicsConfig::caliber_9mm();
+    let solutions = config.solve_launch(Vec3::ZERO, 375.0, 0.0075, Vec3::new(0.0, 0.0, -5000.0));
+
+    assert!(solutions.low.is_none());
+    assert!(solutions.high.is_none());
+}
+
+#[test]
+fn low_arc_hits_the_target_through_drag_and_crosswind() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -300.0, 1.0);
+    let config = BulletPhysicsConfig {
+        environment: Arc::new(ConstantWind {
+            wind: Vec3::new(5.0, 0.0, 0.0),
+            ..default()
+        }),
+        ..BulletPhysicsConfig::caliber_556()
+    };
+    let muzzle = Vec3::new(0.0, 1.5, 0.0);
+    let target = Vec3::new(0.0, 1.0, -300.0);
+
+    let solution = config
+        .solve_launch(muzzle, 900.0, MASS, target)
+        .low
+        .expect("target should be in range");
+    // The wind blows to the right, so the shot has to be aimed left
+    assert!(solution.lead < 0.0);
+
+    let result = common::simulate(&mut app, muzzle, solution.direction * 900.0, MASS, config);
+    let error = result.hit_point.distance(target);
+    assert!(error < 0.01, "missed the target by {error} m");
+    assert!((result.time_of_flight - solution.time_of_flight).abs() < 0.001);
+}
+
+#[test]
+fn intercept_meets_a_moving_target() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -200.0, 1.0);
+    let config = BulletPhysicsConfig::caliber_556();
+    let muzzle = Vec3::new(0.0, 1.5, 0.0);
+    let target = Vec3::new(-5.0, 1.0, -200.0);
+    let target_velocity = Vec3::new(10.0, 0.0, 0.0);
+
+    let intercept = config
+        .solve_intercept(muzzle, 900.0, MASS, target, target_velocity, None)
+        .expect("target should be catchable");
+    let expected = target + target_velocity * intercept.intercept_time;
+    assert!(intercept.intercept_point.distance(expected) < 0.001);
+
+    let velocity = intercept.launch.direction * 900.0;
+    let result = common::simulate(&mut app, muzzle, velocity, MASS, config);
+    let error = result.hit_point.distance(intercept.intercept_point);
+    assert!(error < 0.01, "missed the intercept point by {error} m");
+    assert!((result.time_of_flight - intercept.intercept_time).abs() < 0.001);
+}
+
+#[test]
+fn intercept_accounts_for_target_acceleration() {
+    let config = BulletPhysicsConfig::caliber_556();
+    let muzzle = Vec3::new(0.0, 1.5, 0.0);
+    let target = Vec3::new(-5.0, 1.0, -200.0);
+    let (velocity, acceleration) = (Vec3::new(10.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 20.0));
+
+    let steady = config
+        .solve_intercept(muzzle, 900.0, MASS, target, velocity, None)
+        .expect("target should be catchable");
+    let accelerating = config
+        .solve_intercept(muzzle, 900.0, MASS, target, velocity, Some(acceleration))
+        .expect("target should be catchable");
+
+    // The target closes in while the bullet is in flight
+    let t = accelerating.intercept_time;
+    let expected = target + velocity * t + 0.5 * acceleration * t * t;
+    assert!(accelerating.intercept_point.distance(expected) < 0.001);
+    assert!(accelerating.intercept_time < steady.intercept_time);
+}
@@ -0,0 +1,91 @@
+//! Checks that spread patterns are reproducible and that the parallel batch API matches the serial one.
+
+mod common;
+
+use avian_bullet_trajectory::{
+    BulletPhysicsConfig, BulletSpatialQuery, BulletTrajectory, SpreadPattern,
+};
+use avian3d::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+const VELOCITY: Vec3 = Vec3::new(0.0, 0.0, -400.0);
+
+#[test]
+fn pellets_stay_inside_the_cone() {
+    let spread = SpreadPattern::new(0.05, 64, 12);
+    let velocities = spread.velocities(VELOCITY);
+
+    assert_eq!(velocities.len(), 64);
+    for velocity in velocities {
+        assert!((velocity.length() - 400.0).abs() < 0.01);
+        assert!(velocity.angle_between(VELOCITY) <= 0.05 + 1e-4);
+    }
+}
+
+#[test]
+fn the_same_seed_gives_the_same_pattern() {
+    let first = SpreadPattern::new(0.05, 9, 3).velocities(VELOCITY);
+    let second = SpreadPattern::new(0.05, 9, 3).velocities(VELOCITY);
+    let other = SpreadPattern::new(0.05, 9, 4).velocities(VELOCITY);
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn perturbed_direction_stays_inside_the_cone() {
+    let direction = Dir3::new(VELOCITY).unwrap();
+    for seed in 0..64 {
+        let perturbed = SpreadPattern::perturb(direction, 0.05, seed);
+        assert!(perturbed.angle_between(*direction) <= 0.05 + 1e-4);
+        assert_eq!(perturbed, SpreadPattern::perturb(direction, 0.05, seed));
+    }
+    assert_ne!(
+        SpreadPattern::perturb(direction, 0.05, 3),
+        SpreadPattern::perturb(direction, 0.05, 4)
+    );
+    assert_eq!(SpreadPattern::perturb(direction, 0.0, 3), direction);
+}
+
+#[test]
+fn parallel_spread_matches_serial_spread() {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -50.0, 1.0);
+    let spread = SpreadPattern::new(0.05, 9, 3);
+
+    let (serial, parallel) = app
+        .world_mut()
+        .run_system_once(move |spatial_query: BulletSpatialQuery| {
+            let config = BulletPhysicsConfig::buckshot_00();
+            let filter = SpatialQueryFilter::default();
+            let start = Vec3::new(0.0, 1.5, 0.0);
+            (
+                spatial_query.simulate_spread(
+                    start,
+                    VELOCITY,
+                    0.0035,
+                    &spread,
+                    Some(config.clone()),
+                    &filter,
+                    None,
+                ),
+                spatial_query.par_simulate_spread(
+                    start,
+                    VELOCITY,
+                    0.0035,
+                    &spread,
+                    Some(config),
+                    &filter,
+                    None,
+                ),
+            )
+        })
+        .expect("spread system should run");
+
+    assert_eq!(serial.len(), 9);
+    for (serial, parallel) in serial.iter().zip(&parallel) {
+        assert!(serial.hit_entity.is_some());
+        assert_eq!(serial.hit_entity, parallel.hit_entity);
+        assert_eq!(serial.hit_point, parallel.hit_point);
+    }
+}
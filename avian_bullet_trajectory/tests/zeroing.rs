@@ -0,0 +1,84 @@
+//! Fires zeroed weapons at a wall placed at their zero distance and checks they hit the point of aim.
+
+mod common;
+
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, ConstantWind, Zeroing};
+use bevy::prelude::*;
+
+const SIGHT: Vec3 = Vec3::new(0.0, 1.6, 0.0);
+
+/// Fires through a level sight at `SIGHT` and returns the hit height relative to the point of aim
+fn impact_offset(
+    zeroing: Zeroing,
+    muzzle_speed: f32,
+    mass: f32,
+    config: BulletPhysicsConfig,
+    distance: f32,
+) -> f32 {
+    let mut app = common::physics_app();
+    common::spawn_wall(&mut app, -distance, 1.0);
+    let elevation = zeroing
+        .elevation(muzzle_speed, mass, &config)
+        .expect("zero distance should be in range");
+    let sight = Transform::from_translation(SIGHT).looking_to(Vec3::NEG_Z, Vec3::Y);
+    let (muzzle, direction) = zeroing.launch(&sight, elevation);
+    assert!((muzzle.y - (SIGHT.y - zeroing.sight_height)).abs() < 1e-6);
+
+    let result = common::simulate(&mut app, muzzle, direction * muzzle_speed, mass, config);
+    assert!(result.hit_entity.is_some(), "should hit the wall");
+    result.hit_point.y - SIGHT.y
+}
+
+#[test]
+fn pistol_hits_point_of_aim_at_zero() {
+    let zeroing = Zeroing::new(0.02, 25.0);
+    let config = BulletPhysicsConfig::caliber_9mm();
+    let offset = impact_offset(zeroing, 375.0, 0.0075, config, 25.0);
+    assert!(offset.abs() < 0.002, "{offset} m off the point of aim");
+}
+
+#[test]
+fn rifle_hits_point_of_aim_at_zero() {
+    for zero_distance in [100.0, 300.0] {
+        let zeroing = Zeroing::new(0.07, zero_distance);
+        let config = BulletPhysicsConfig::caliber_556();
+        let offset = impact_offset(zeroing, 900.0, 0.004, config, zero_distance);
+        assert!(offset.abs() < 0.002, "{offset} m off the point of aim");
+    }
+}
+
+#[test]
+fn rifle_shoots_high_inside_and_low_beyond_the_zero() {
+    let zeroing = Zeroing::new(0.07, 300.0);
+    let config = BulletPhysicsConfig::caliber_556();
+
+    // Past the near zero the bullet is above the line of sight until it comes back down
+    let inside = impact_offset(zeroing, 900.0, 0.004, config.clone(), 150.0);
+    let beyond = impact_offset(zeroing, 900.0, 0.004, config, 400.0);
+    assert!(inside > 0.0, "{inside} m should be above the point of aim");
+    assert!(beyond < 0.0, "{beyond} m should be below the point of aim");
+}
+
+#[test]
+fn wind_does_not_change_the_zero() {
+    let zeroing = Zeroing::new(0.07, 300.0);
+    let still = zeroing
+        .elevation(900.0, 0.004, &BulletPhysicsConfig::caliber_556())
+        .expect("zero distance should be in range");
+    // The zero is solved along an arbitrary horizontal axis, so blow along every one of them
+    for wind in [Vec3::X, Vec3::Z, Vec3::NEG_X, Vec3::NEG_Z] {
+        let config = BulletPhysicsConfig {
+            environment: Arc::new(ConstantWind {
+                wind: wind * 10.0,
+                ..default()
+            }),
+            ..BulletPhysicsConfig::caliber_556()
+        };
+        let elevation = zeroing
+            .elevation(900.0, 0.004, &config)
+            .expect("zero distance should be in range");
+        assert_eq!(elevation, still, "zero shifted in a {wind} wind");
+    }
+}
@@ -0,0 +1,234 @@
+//! Aiming down the sights of the held weapon.
+//!
+//! Aiming brings the weapon up to the eye and narrows the field of view. Scoped weapons swap the
+//! viewmodel for a reticle once the scope is up, with holdover marks for the round they fire.
+
+use std::f32::consts::FRAC_PI_4;
+
+use avian_bullet_trajectory::BulletPhysicsConfig;
+use bevy::{prelude::*, ui::Val::*};
+use bevy_enhanced_input::prelude::*;
+
+use crate::screens::Screen;
+
+use super::{
+    ammo::Reloading,
+    level::LevelEnvironment,
+    target::Aim,
+    weapon::{Weapon, WeaponDefinition},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            build_scope_overlay,
+            raise_sights,
+            (zoom_camera, show_scope_overlay),
+        )
+            .chain(),
+    );
+}
+
+/// Vertical field of view of the camera when not aiming, in radians
+pub const BASE_FOV: f32 = FRAC_PI_4;
+
+/// How far the sights are raised, from 0 at the hip to 1 fully aimed
+#[derive(Component, Debug, Clone, Copy, Default, Deref, DerefMut)]
+pub struct AimBlend(pub f32);
+
+/// Reticle shown instead of the viewmodel while looking through a scope
+#[derive(Component, Debug, Clone, Copy)]
+struct ScopeOverlay;
+
+fn raise_sights(
+    time: Res<Time>,
+    aim: Single<&Action<Aim>>,
+    weapon: Single<(&Weapon, &mut AimBlend, Has<Reloading>)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    let (weapon, mut blend, reloading) = weapon.into_inner();
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    // Reloading takes the weapon away from the eye
+    let target = if ***aim && !reloading { 1.0 } else { 0.0 };
+    let step = time.delta_secs() / definition.sights.aim_time.max(f32::EPSILON);
+    **blend += (target - **blend).clamp(-step, step);
+}
+
+fn zoom_camera(
+    mut projection: Single<&mut Projection, With<Camera3d>>,
+    weapon: Option<Single<(&Weapon, &AimBlend)>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    let zoom = weapon
+        .and_then(|weapon| {
+            let (weapon, blend) = *weapon;
+            let magnification = definitions.get(&weapon.0)?.sights.magnification();
+            Some(1.0 + (magnification - 1.0) * **blend)
+        })
+        .unwrap_or(1.0);
+    if let Projection::Perspective(perspective) = &mut **projection {
+        perspective.fov = BASE_FOV / zoom;
+    }
+}
+
+/// Replaces the scope overlay with one for the held weapon, if it has a scope
+///
+/// The holdover marks depend on the weapon's definition and the level's air, so the overlay is
+/// rebuilt whenever the weapon is inserted, which a hot-reload of its definition also does, or
+/// the level's environment changes.
+fn build_scope_overlay(
+    weapon: Option<Single<Ref<Weapon>>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    environment: Res<LevelEnvironment>,
+    overlays: Query<Entity, With<ScopeOverlay>>,
+    mut commands: Commands,
+) {
+    let weapon_changed = weapon.as_ref().is_some_and(|weapon| weapon.is_changed());
+    if !weapon_changed && !environment.is_changed() {
+        return;
+    }
+    for overlay in &overlays {
+        commands.entity(overlay).despawn();
+    }
+    let Some(weapon) = weapon else {
+        return;
+    };
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    let Some(scope) = definition.sights.scope else {
+        return;
+    };
+
+    // Putting the mark for a target's range on it makes up for the drop past the zero
+    let config = BulletPhysicsConfig {
+        environment: environment.0.clone(),
+        ..definition.ballistics()
+    };
+    let card = config
+        .range_card(
+            definition.bullet_mass,
+            definition.muzzle_velocity,
+            scope.mark_spacing,
+            scope.max_range,
+            Some(&definition.zeroing()),
+        )
+        .unwrap_or_else(|| {
+            tracing::warn!(weapon = definition.name, "Zero Out Of Reach");
+            default()
+        });
+    // Screen height is the scope's field of view, so marks go down from the center by the
+    // tangent of their angle over the tangent of half of it
+    let half_view = (BASE_FOV / scope.magnification / 2.0).tan();
+    let marks = card
+        .rows
+        .iter()
+        .filter(|row| row.range > definition.zero_distance)
+        .map(|row| (row.range, 50.0 + row.drop_angle.tan() / half_view * 50.0))
+        .collect::<Vec<_>>();
+
+    commands
+        .spawn((
+            Name::new("Scope Overlay"),
+            ScopeOverlay,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Percent(100.0),
+                height: Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|overlay| {
+            // The outline blacks out everything around the lens
+            overlay.spawn((
+                Name::new("Lens"),
+                Node {
+                    height: Percent(95.0),
+                    aspect_ratio: Some(1.0),
+                    ..default()
+                },
+                BorderRadius::MAX,
+                Outline::new(Vw(100.0), Px(0.0), Color::BLACK),
+                children![reticle_line(false), reticle_line(true)],
+            ));
+            for (range, top) in marks {
+                overlay.spawn((
+                    Name::new(format!("Holdover {range} m")),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        top: Percent(top),
+                        left: Percent(50.0),
+                        width: Px(24.0),
+                        height: Px(1.0),
+                        margin: UiRect::left(Px(-12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK),
+                    children![(
+                        Name::new("Range"),
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Px(28.0),
+                            top: Px(-7.0),
+                            ..default()
+                        },
+                        Text(format!("{range}")),
+                        TextFont::from_font_size(12.0),
+                        TextColor(Color::BLACK),
+                    )],
+                ));
+            }
+        });
+}
+
+/// A black line across the middle of the lens
+fn reticle_line(vertical: bool) -> impl Bundle {
+    let node = if vertical {
+        Node {
+            position_type: PositionType::Absolute,
+            left: Percent(50.0),
+            width: Px(1.0),
+            height: Percent(100.0),
+            ..default()
+        }
+    } else {
+        Node {
+            position_type: PositionType::Absolute,
+            top: Percent(50.0),
+            width: Percent(100.0),
+            height: Px(1.0),
+            ..default()
+        }
+    };
+    (
+        Name::new("Reticle Line"),
+        node,
+        BackgroundColor(Color::BLACK),
+    )
+}
+
+fn show_scope_overlay(
+    weapon: Single<(&AimBlend, &mut Visibility), With<Weapon>>,
+    mut overlay: Single<&mut Visibility, (With<ScopeOverlay>, Without<Weapon>)>,
+) {
+    let (blend, mut viewmodel) = weapon.into_inner();
+    let scoped = **blend >= 1.0;
+    overlay.set_if_neq(if scoped {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    viewmodel.set_if_neq(if scoped {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    });
+}
@@ -0,0 +1,284 @@
+//! Magazines, spare rounds and reloading for the held weapon.
+//!
+//! A weapon fires the round in its chamber and chambers the next one from the magazine. Reloading
+//! with a round still chambered (a tactical reload) is quicker than once the weapon has run dry,
+//! which also has to chamber a fresh round.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::audio::sound_effect;
+
+use super::{
+    layers::GameLayer,
+    target::{PickedUp, Reload},
+    weapon::{Caliber, Weapon, WeaponDefinition},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(load_new_weapon);
+    app.add_observer(start_reload);
+    app.add_observer(setup_ammo_pickup);
+    app.add_observer(pickup_ammo);
+    app.add_systems(Update, finish_reload);
+}
+
+/// Rounds left in a weapon
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ammo {
+    /// Whether there's a round in the chamber, ready to fire
+    pub chambered: bool,
+    /// Rounds in the magazine
+    pub magazine: u32,
+    /// Spare rounds to reload from
+    pub reserve: u32,
+}
+
+impl Ammo {
+    /// A full magazine with a round chambered, and the weapon's spare rounds
+    pub fn full(definition: &WeaponDefinition) -> Self {
+        Self {
+            chambered: true,
+            magazine: definition.magazine_size,
+            reserve: definition.reserve,
+        }
+    }
+
+    /// Fires the chambered round and chambers the next one from the magazine.
+    /// Returns `false` if the chamber was empty.
+    pub fn fire(&mut self) -> bool {
+        if !self.chambered {
+            return false;
+        }
+        self.chambered = self.magazine > 0;
+        self.magazine = self.magazine.saturating_sub(1);
+        true
+    }
+
+    /// The reload a weapon holding `magazine_size` rounds needs, if a reload would add any
+    pub fn reload_kind(&self, magazine_size: u32) -> Option<ReloadKind> {
+        if self.reserve == 0 || (self.chambered && self.magazine >= magazine_size) {
+            return None;
+        }
+        Some(if self.chambered {
+            ReloadKind::Tactical
+        } else {
+            ReloadKind::Empty
+        })
+    }
+
+    /// Tops up the magazine from the reserve, chambering a round if there wasn't one
+    pub fn reload(&mut self, magazine_size: u32) {
+        // Rounds left in the old magazine go back into the reserve
+        self.reserve += self.magazine;
+        self.magazine = self.reserve.min(magazine_size);
+        self.reserve -= self.magazine;
+        if !self.chambered && self.magazine > 0 {
+            self.chambered = true;
+            self.magazine -= 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadKind {
+    /// A round is still chambered, so only the magazine is swapped
+    Tactical,
+    /// The weapon ran dry, so a round has to be chambered as well
+    Empty,
+}
+
+/// A reload in progress. The weapon can't fire until it's done.
+#[derive(Component, Debug, Clone)]
+pub struct Reloading {
+    pub kind: ReloadKind,
+    pub timer: Timer,
+}
+
+fn load_new_weapon(
+    event: On<Add, Weapon>,
+    weapons: Query<&Weapon>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    mut commands: Commands,
+) {
+    if let Ok(weapon) = weapons.get(event.entity)
+        && let Some(definition) = definitions.get(&weapon.0)
+    {
+        commands.entity(event.entity).insert(Ammo::full(definition));
+    }
+}
+
+fn start_reload(
+    _trigger: On<Fire<Reload>>,
+    weapon: Single<(Entity, &Weapon, &Ammo), Without<Reloading>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let (entity, weapon, ammo) = *weapon;
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    let Some(kind) = ammo.reload_kind(definition.magazine_size) else {
+        return;
+    };
+    info!(?kind, ?ammo, "Reloading");
+    let time = match kind {
+        ReloadKind::Tactical => definition.reload_time,
+        ReloadKind::Empty => definition.empty_reload_time,
+    };
+    commands.entity(entity).insert(Reloading {
+        kind,
+        timer: Timer::from_seconds(time, TimerMode::Once),
+    });
+    if let Some(sound) = &definition.sounds.reload {
+        commands.spawn(sound_effect(asset_server.load(sound.clone())));
+    }
+}
+
+fn finish_reload(
+    time: Res<Time>,
+    mut weapons: Query<(Entity, &Weapon, &mut Ammo, &mut Reloading)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    mut commands: Commands,
+) {
+    for (entity, weapon, mut ammo, mut reloading) in &mut weapons {
+        if !reloading.timer.tick(time.delta()).is_finished() {
+            continue;
+        }
+        if let Some(definition) = definitions.get(&weapon.0) {
+            ammo.reload(definition.magazine_size);
+        }
+        info!(?ammo, "Reloaded");
+        commands.entity(entity).remove::<Reloading>();
+    }
+}
+
+/// A box of rounds for weapons of one caliber
+#[point_class(base(Transform))]
+#[reflect(Component)]
+pub struct AmmoPickup {
+    pub caliber: Caliber,
+    pub rounds: u32,
+}
+
+impl Default for AmmoPickup {
+    fn default() -> Self {
+        Self {
+            caliber: Caliber::default(),
+            rounds: 30,
+        }
+    }
+}
+
+fn setup_ammo_pickup(
+    event: On<Add, AmmoPickup>,
+    mut commands: Commands,
+    mut mesh_assets: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let entity = event.entity;
+    tracing::info!(?entity, "Setting Up Spawned Ammo Pickup");
+    commands.entity(entity).insert((
+        Name::new("AmmoPickup"),
+        Mesh3d(mesh_assets.add(Cuboid::new(0.3, 0.15, 0.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.35, 0.2))),
+        Sensor,
+        Collider::cuboid(0.3, 0.15, 0.2),
+        CollisionLayers::new(GameLayer::Pickup, GameLayer::Player),
+    ));
+}
+
+/// Adds the rounds of an ammo pickup the player picked up to the held weapon, if they fit it
+fn pickup_ammo(
+    event: On<PickedUp>,
+    mut commands: Commands,
+    pickups: Query<&AmmoPickup>,
+    weapon: Single<(&Weapon, &mut Ammo)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    let (weapon, mut ammo) = weapon.into_inner();
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    if let Ok(pickup) = pickups.get(event.entity)
+        && pickup.caliber == definition.caliber
+    {
+        ammo.reserve += pickup.rounds;
+        info!(
+            rounds = pickup.rounds,
+            reserve = ammo.reserve,
+            "Picked up ammo"
+        );
+        commands.entity(event.entity).try_despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGAZINE_SIZE: u32 = 30;
+
+    fn rounds(chambered: bool, magazine: u32, reserve: u32) -> Ammo {
+        Ammo {
+            chambered,
+            magazine,
+            reserve,
+        }
+    }
+
+    #[test]
+    fn fires_the_magazine_then_the_chambered_round() {
+        let mut ammo = rounds(true, 2, 0);
+        assert!(ammo.fire());
+        assert_eq!(ammo, rounds(true, 1, 0));
+        assert!(ammo.fire());
+        assert_eq!(ammo, rounds(true, 0, 0));
+        // The last round was chambered with the magazine already empty
+        assert!(ammo.fire());
+        assert_eq!(ammo, rounds(false, 0, 0));
+        assert!(!ammo.fire());
+        assert_eq!(ammo, rounds(false, 0, 0));
+    }
+
+    #[test]
+    fn tactical_reload_keeps_the_chambered_round() {
+        let mut ammo = rounds(true, 10, 90);
+        assert_eq!(ammo.reload_kind(MAGAZINE_SIZE), Some(ReloadKind::Tactical));
+        ammo.reload(MAGAZINE_SIZE);
+        assert_eq!(ammo, rounds(true, 30, 70));
+    }
+
+    #[test]
+    fn empty_reload_chambers_a_round_from_the_magazine() {
+        let mut ammo = rounds(false, 0, 90);
+        assert_eq!(ammo.reload_kind(MAGAZINE_SIZE), Some(ReloadKind::Empty));
+        ammo.reload(MAGAZINE_SIZE);
+        assert_eq!(ammo, rounds(true, 29, 60));
+    }
+
+    #[test]
+    fn reserve_smaller_than_the_magazine_goes_in_whole() {
+        let mut ammo = rounds(true, 5, 12);
+        ammo.reload(MAGAZINE_SIZE);
+        assert_eq!(ammo, rounds(true, 17, 0));
+
+        let mut ammo = rounds(false, 0, 1);
+        ammo.reload(MAGAZINE_SIZE);
+        assert_eq!(ammo, rounds(true, 0, 0));
+    }
+
+    #[test]
+    fn no_reload_without_a_reserve_or_with_a_full_magazine() {
+        assert_eq!(rounds(true, 3, 0).reload_kind(MAGAZINE_SIZE), None);
+        assert_eq!(rounds(false, 0, 0).reload_kind(MAGAZINE_SIZE), None);
+        assert_eq!(rounds(true, 30, 90).reload_kind(MAGAZINE_SIZE), None);
+
+        let mut ammo = rounds(true, 3, 0);
+        ammo.reload(MAGAZINE_SIZE);
+        assert_eq!(ammo, rounds(true, 3, 0));
+    }
+}
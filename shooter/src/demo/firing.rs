@@ -0,0 +1,119 @@
+//! Fire modes and rate of fire of the held weapon.
+//!
+//! The trigger is read every fixed step, so weapons keep their cadence whatever the frame rate.
+
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::audio::sound_effect;
+
+use super::{
+    ammo::{Ammo, Reloading},
+    target::{SelectFireMode, Shoot},
+    weapon::{FireMode, Weapon, WeaponDefinition},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(pull_trigger);
+    app.add_observer(select_fire_mode);
+    app.add_systems(FixedUpdate, cycle_weapon);
+}
+
+/// A round left the held weapon
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WeaponFired;
+
+/// Trigger and action of a weapon
+#[derive(Component, Debug, Clone, Default)]
+pub struct FireControl {
+    /// Index of the selected mode in the weapon's fire modes
+    pub mode: usize,
+    /// Seconds until the action has cycled and the next round can fire
+    pub cooldown: f32,
+    /// Rounds still to fire for the last trigger pull
+    pub pending: u32,
+    /// Whether the trigger was pulled since the last fixed step
+    pub pulled: bool,
+}
+
+impl FireControl {
+    /// The selected fire mode, semi-auto if the weapon doesn't list any
+    pub fn mode(&self, definition: &WeaponDefinition) -> FireMode {
+        definition
+            .fire_modes
+            .get(self.mode)
+            .copied()
+            .unwrap_or(FireMode::Semi)
+    }
+}
+
+fn pull_trigger(_trigger: On<Start<Shoot>>, mut control: Single<&mut FireControl>) {
+    control.pulled = true;
+}
+
+fn select_fire_mode(
+    _trigger: On<Fire<SelectFireMode>>,
+    weapon: Single<(&Weapon, &mut FireControl)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    let (weapon, mut control) = weapon.into_inner();
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    control.mode = (control.mode + 1) % definition.fire_modes.len().max(1);
+    control.pending = 0;
+    info!(mode = ?control.mode(definition), "Selected fire mode");
+}
+
+fn cycle_weapon(
+    time: Res<Time>,
+    weapon: Single<(&Weapon, &mut FireControl, &mut Ammo, Has<Reloading>)>,
+    shoot: Single<&Action<Shoot>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let (weapon, mut control, mut ammo, reloading) = weapon.into_inner();
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    let pulled = std::mem::take(&mut control.pulled) && !reloading;
+    let held = ***shoot && !reloading;
+
+    match control.mode(definition) {
+        FireMode::Semi => {
+            if pulled {
+                control.pending = 1;
+            }
+        }
+        FireMode::Burst(rounds) => {
+            if pulled && control.pending == 0 {
+                control.pending = rounds;
+            }
+        }
+        FireMode::Auto => control.pending = u32::from(held || pulled),
+    }
+    if reloading {
+        control.pending = 0;
+    }
+
+    control.cooldown -= time.delta_secs();
+    while control.pending > 0 && control.cooldown <= 0.0 {
+        control.pending -= 1;
+        if !ammo.fire() {
+            // Pulling the trigger on an empty chamber just clicks
+            control.pending = 0;
+            if pulled {
+                info!("Out of ammo");
+                if let Some(sound) = &definition.sounds.dry_fire {
+                    commands.spawn(sound_effect(asset_server.load(sound.clone())));
+                }
+            }
+            break;
+        }
+        control.cooldown += 60.0 / definition.rounds_per_minute;
+        commands.trigger(WeaponFired);
+    }
+    // Time spent waiting doesn't bank shots for later
+    control.cooldown = control.cooldown.max(0.0);
+}
@@ -0,0 +1,31 @@
+//! Physics layers, so bullets and queries only see what they should.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(PhysicsLayer, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameLayer {
+    /// Level geometry and loose props, where colliders without [`CollisionLayers`] end up
+    #[default]
+    World,
+    /// The player's character body
+    Player,
+    /// Things to shoot at, like targets
+    Enemy,
+    /// Colliders that take damage for a body, like a head or a limb
+    Hitbox,
+    /// Areas the player can pick things up from
+    Pickup,
+    /// Trigger volumes
+    Trigger,
+}
+
+impl GameLayer {
+    /// Layers that stop bullets
+    pub const SHOOTABLE: [GameLayer; 3] = [GameLayer::World, GameLayer::Enemy, GameLayer::Hitbox];
+
+    /// Filter for bullets fired by `shooter`, so they can't hit the body they were fired from
+    pub fn bullet_filter(shooter: Entity) -> SpatialQueryFilter {
+        SpatialQueryFilter::from_mask(Self::SHOOTABLE).with_excluded_entities([shooter])
+    }
+}
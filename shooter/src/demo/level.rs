@@ -1,5 +1,10 @@
 //! Spawn the main level.
 
+use std::sync::Arc;
+
+use avian_bullet_trajectory::{
+    BallisticEnvironment, BulletPhysicsConfig, GustingWind, StandardAtmosphere,
+};
 use bevy::{
     prelude::*,
     window::{CursorGrabMode, CursorOptions, PrimaryWindow},
@@ -11,6 +16,10 @@ use crate::{asset_tracking::LoadResource, screens::Screen};
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(setup_sphere);
     app.load_resource::<LevelAssets>();
+
+    app.init_resource::<LevelEnvironment>();
+    app.add_observer(apply_world_environment);
+    app.add_observer(reset_world_environment);
 }
 
 #[derive(Component, Debug, Reflect)]
@@ -65,3 +74,59 @@ fn setup_sphere(
         MeshMaterial3d(materials.add(StandardMaterial::default())),
     ));
 }
+
+/// The air bullets fly through in the current level.
+///
+/// Set from the map's [`WorldEnvironment`] entity, or still air at sea level if there is none.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct LevelEnvironment(pub Arc<dyn BallisticEnvironment>);
+
+impl Default for LevelEnvironment {
+    fn default() -> Self {
+        Self(BulletPhysicsConfig::default().environment)
+    }
+}
+
+/// Wind and atmosphere settings for the level
+#[point_class]
+#[derive(Default)]
+#[reflect(Component)]
+pub struct WorldEnvironment {
+    /// Mean wind velocity in m/s, in world space with Y up
+    pub wind: Vec3,
+    /// Peak gust speed on top of the mean wind in m/s
+    pub gust_strength: f32,
+    /// Typical duration of a gust in seconds
+    pub gust_period: f32,
+    /// Seed for the gust pattern
+    pub gust_seed: u32,
+    /// Altitude of the map origin above sea level in meters
+    pub altitude: f32,
+    /// Deviation from the standard temperature of 15 °C
+    pub temperature_offset: f32,
+}
+
+fn apply_world_environment(
+    event: On<Add, WorldEnvironment>,
+    environments: Query<&WorldEnvironment>,
+    mut commands: Commands,
+) {
+    let Ok(environment) = environments.get(event.entity) else {
+        return;
+    };
+    tracing::info!(wind = ?environment.wind, "Applying World Environment");
+    commands.insert_resource(LevelEnvironment(Arc::new(GustingWind {
+        base: StandardAtmosphere {
+            ground_altitude: environment.altitude,
+            temperature_offset: environment.temperature_offset,
+            wind: environment.wind,
+        },
+        gust_strength: environment.gust_strength,
+        gust_period: environment.gust_period,
+        seed: environment.gust_seed as u64,
+    })));
+}
+
+fn reset_world_environment(_event: On<Remove, WorldEnvironment>, mut commands: Commands) {
+    commands.insert_resource(LevelEnvironment::default());
+}
@@ -5,18 +5,31 @@
 
 use bevy::prelude::*;
 
+pub mod aim;
+pub mod ammo;
 pub mod debug;
+pub mod firing;
+pub mod layers;
 pub mod level;
 mod movement;
 pub mod player;
+pub mod recoil;
+pub mod surface;
 pub mod target;
+pub mod weapon;
 
 pub fn plugin(app: &mut App) {
     app.add_plugins((
+        aim::plugin,
+        ammo::plugin,
         debug::plugin,
+        firing::plugin,
         level::plugin,
         movement::plugin,
         player::plugin,
+        recoil::plugin,
+        surface::plugin,
         target::plugin,
+        weapon::plugin,
     ));
 }
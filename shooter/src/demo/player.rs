@@ -6,15 +6,21 @@ use bevy_enhanced_input::prelude::*;
 use bevy_tnua::prelude::TnuaController;
 use bevy_trenchbroom::prelude::*;
 
-use avian_bullet_trajectory::BulletPhysicsConfig;
 use avian3d::prelude::*;
 
+use super::{
+    aim::BASE_FOV,
+    layers::GameLayer,
+    recoil::ViewKick,
+    weapon::{WeaponDefinition, definition_path},
+};
+
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(setup_player)
         .add_observer(handled_player_looking)
         .add_observer(setup_weapon_spawner);
 
-    app.add_systems(Update, sync_player_camera);
+    app.add_systems(Update, (sync_player_camera, finish_weapon_spawner));
 }
 
 /// The player character.
@@ -31,6 +37,7 @@ fn setup_player(event: On<Add, Player>, mut commands: Commands) {
         super::target::WeaponContext,
         RigidBody::Dynamic,
         Collider::capsule(0.29, 1.0),
+        CollisionLayers::new(GameLayer::Player, LayerMask::ALL),
         TnuaController::default(),
         LockedAxes::ROTATION_LOCKED,
         children![(
@@ -41,51 +48,29 @@ fn setup_player(event: On<Add, Player>, mut commands: Commands) {
     ));
 }
 
-#[point_class(model({ path: "models/fnf2000.glb" }), base(Transform))]
-#[derive(Default)]
+/// Spawns a weapon the player can pick up
+///
+/// Shown as a plain box in the editor: the model comes from the weapon's definition, which is
+/// only known from its id once the game loads it.
+#[point_class(base(Transform))]
 #[reflect(Component)]
 pub struct WeaponSpawner {
-    pub weapon: WeaponType,
-}
-
-#[derive(Component, FgdType, Default, Reflect, Clone, Copy)]
-#[reflect(Component)]
-pub enum WeaponType {
-    #[default]
-    Glock,
-    FNF2000,
+    /// Id of the weapon's definition, its file name in `assets/weapons` without `.weapon.ron`
+    pub weapon: String,
 }
 
-impl WeaponType {
-    pub fn name(&self) -> String {
-        match self {
-            WeaponType::Glock => "Glock".to_string(),
-            WeaponType::FNF2000 => "FN F2000".to_string(),
-        }
-    }
-
-    pub fn model(&self) -> String {
-        match self {
-            WeaponType::Glock => "models/glock.glb#Scene0".to_string(),
-            WeaponType::FNF2000 => "models/fnf2000.glb#Scene0".to_string(),
-        }
-    }
-
-    pub fn ballistics(&self) -> BulletPhysicsConfig {
-        match self {
-            WeaponType::Glock => BulletPhysicsConfig::caliber_9mm(),
-            WeaponType::FNF2000 => BulletPhysicsConfig::caliber_556(),
-        }
-    }
-
-    pub fn muzzle_velocity(&self) -> f32 {
-        match self {
-            WeaponType::Glock => 375.0,
-            WeaponType::FNF2000 => 900.0,
+impl Default for WeaponSpawner {
+    fn default() -> Self {
+        Self {
+            weapon: "glock".to_string(),
         }
     }
 }
 
+/// The definition of the weapon a spawner holds, kept loaded for as long as the spawner exists
+#[derive(Component, Debug, Clone, Deref)]
+pub struct WeaponSpawnerDefinition(pub Handle<WeaponDefinition>);
+
 fn setup_weapon_spawner(
     event: On<Add, WeaponSpawner>,
     mut commands: Commands,
@@ -93,12 +78,43 @@ fn setup_weapon_spawner(
     asset_server: Res<AssetServer>,
 ) {
     tracing::info!("Setting Up Spawned Weaponer Spawner");
-    if let Ok(spawner) = spawner.get(event.entity) {
-        commands.entity(event.entity).insert((
-            Name::new("WeaponSpawner"),
+    let Ok(spawner) = spawner.get(event.entity) else {
+        return;
+    };
+    commands.entity(event.entity).insert((
+        Name::new("WeaponSpawner"),
+        WeaponSpawnerDefinition(asset_server.load(definition_path(&spawner.weapon))),
+    ));
+}
+
+/// Gives spawners their model and pickup area once their weapon's definition has loaded
+///
+/// Definitions load when the map spawns, so spawners are checked again every frame until theirs
+/// is in, or has failed to load.
+fn finish_weapon_spawner(
+    spawners: Query<(Entity, &WeaponSpawner, &WeaponSpawnerDefinition), Without<SceneRoot>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, spawner, handle) in &spawners {
+        let Some(definition) = definitions.get(&handle.0) else {
+            if asset_server.load_state(handle.id()).is_failed() {
+                tracing::warn!(weapon = spawner.weapon, "Unknown Weapon");
+                commands.entity(entity).remove::<WeaponSpawnerDefinition>();
+            }
+            continue;
+        };
+        commands.entity(entity).insert((
             RigidBody::Dynamic,
             Collider::cuboid(0.08, 0.2, 0.6),
-            SceneRoot(asset_server.load(spawner.weapon.model())),
+            SceneRoot(asset_server.load(definition.model.clone())),
+            children![(
+                Name::new("PickupArea"),
+                Sensor,
+                Collider::sphere(0.5),
+                CollisionLayers::new(GameLayer::Pickup, GameLayer::Player),
+            )],
         ));
     }
 }
@@ -110,25 +126,42 @@ pub struct Player;
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
 #[reflect(Component)]
+#[require(ViewKick)]
 pub struct PlayerView;
 
+/// Turns the view left by `yaw` and up by `pitch` radians, short of looking straight up or down
+///
+/// Returns how far it actually turned as `(yaw, pitch)`, which is less pitch near the limits.
+pub fn turn_view(view: &mut Transform, yaw: f32, pitch: f32) -> Vec2 {
+    let (current_yaw, current_pitch, _) = view.rotation.to_euler(EulerRot::YXZ);
+    let new_pitch = (current_pitch + pitch).clamp(-1.57, 1.57);
+    view.rotation = Quat::from_euler(EulerRot::YXZ, current_yaw + yaw, new_pitch, 0.0);
+    Vec2::new(yaw, new_pitch - current_pitch)
+}
+
 fn handled_player_looking(
     event: On<Fire<super::movement::Look>>,
     mut player_view: Single<&mut Transform, With<PlayerView>>,
     time: Res<Time>,
     window: Single<&Window, With<bevy::window::PrimaryWindow>>,
+    camera: Single<&Projection, With<Camera3d>>,
 ) {
     if !window.focused {
         return;
     }
-    let sensitivity = 100.0 / window.width().min(window.height());
+    // Turn slower while zoomed in, so things under the crosshair move at the same speed
+    let zoom = match *camera {
+        Projection::Perspective(perspective) => perspective.fov / BASE_FOV,
+        _ => 1.0,
+    };
+    let sensitivity = 100.0 / window.width().min(window.height()) * zoom;
     let delta = time.delta_secs() * sensitivity;
-    let (mut yaw, mut pitch, _) = player_view.rotation.to_euler(EulerRot::YXZ);
-    tracing::debug!(yaw = yaw, pitch = pitch, "Player is Looking Around");
-    yaw += event.value.y * delta;
-    pitch += event.value.x * delta;
-    pitch = pitch.clamp(-1.57, 1.57);
-    player_view.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+    tracing::debug!(value = ?event.value, "Player is Looking Around");
+    turn_view(
+        &mut player_view,
+        event.value.y * delta,
+        event.value.x * delta,
+    );
 }
 
 fn sync_player_camera(
@@ -0,0 +1,118 @@
+//! Recoil, bloom and sway of the held weapon.
+//!
+//! Every shot kicks the view up and sideways along the weapon's recoil pattern. Once the
+//! shooting stops, the view settles back to where it was aimed.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    aim::AimBlend,
+    firing::WeaponFired,
+    player::{Player, PlayerView, turn_view},
+    weapon::{VIEWMODEL_POSITION, Weapon, WeaponDefinition},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(kick_view);
+    app.add_systems(Update, (recover_from_recoil, recover_bloom, sway_viewmodel));
+}
+
+/// Shots further apart than this in seconds start a new string from the top of the pattern
+const STRING_GAP: f32 = 0.3;
+/// Seconds after a shot before the view starts to settle
+const RECOVERY_DELAY: f32 = 0.1;
+
+/// Recoil the view has yet to settle from
+#[derive(Component, Debug, Clone, Default)]
+pub struct ViewKick {
+    /// Kick left to recover as `(right, up)` in radians
+    pub offset: Vec2,
+    /// Shots fired in the current string
+    pub shots: usize,
+    /// Seconds since the last shot
+    pub since_shot: f32,
+}
+
+/// Extra spread from firing in quick succession, in radians
+#[derive(Component, Debug, Clone, Copy, Default, Deref, DerefMut)]
+pub struct Bloom(pub f32);
+
+fn kick_view(
+    _event: On<WeaponFired>,
+    weapon: Single<&Weapon>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    view: Single<(&mut Transform, &mut ViewKick), With<PlayerView>>,
+) {
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    let (mut transform, mut kick) = view.into_inner();
+    if kick.since_shot > STRING_GAP {
+        kick.shots = 0;
+    }
+    let (right, up) = definition.recoil.kick(kick.shots);
+    kick.shots += 1;
+    kick.since_shot = 0.0;
+    // Only what the view actually turned has to be settled from, not kick lost to the pitch limit
+    let turned = turn_view(&mut transform, -right, up);
+    kick.offset += Vec2::new(-turned.x, turned.y);
+}
+
+fn recover_from_recoil(
+    time: Res<Time>,
+    weapon: Option<Single<&Weapon>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    view: Single<(&mut Transform, &mut ViewKick), With<PlayerView>>,
+) {
+    let (mut transform, mut kick) = view.into_inner();
+    kick.since_shot += time.delta_secs();
+    if kick.since_shot < RECOVERY_DELAY || kick.offset == Vec2::ZERO {
+        return;
+    }
+    // Without a weapon to go by, settle right away
+    let recovery = weapon
+        .and_then(|weapon| definitions.get(&weapon.0))
+        .map_or(f32::INFINITY, |definition| definition.recoil.recovery);
+    let step = kick.offset.clamp_length_max(recovery * time.delta_secs());
+    kick.offset -= step;
+    turn_view(&mut transform, step.x, -step.y);
+}
+
+fn recover_bloom(
+    time: Res<Time>,
+    mut weapons: Query<(&Weapon, &mut Bloom)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    for (weapon, mut bloom) in &mut weapons {
+        if let Some(definition) = definitions.get(&weapon.0) {
+            **bloom = (**bloom - definition.accuracy.bloom_recovery * time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+/// Bobs the held weapon gently around where it rests, more so while moving and less while aiming
+fn sway_viewmodel(
+    time: Res<Time>,
+    player: Single<&LinearVelocity, With<Player>>,
+    weapon: Single<(&Weapon, &AimBlend, &mut Transform)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+) {
+    let (weapon, blend, mut transform) = weapon.into_inner();
+    let aimed = definitions
+        .get(&weapon.0)
+        .map_or(VIEWMODEL_POSITION, |definition| {
+            definition.sights.position()
+        });
+    let t = time.elapsed_secs();
+    let speed = Vec2::new(player.x, player.z).length();
+    let amount = (1.0 + speed * 0.15) * (1.0 - 0.8 * **blend);
+    transform.translation = VIEWMODEL_POSITION.lerp(aimed, **blend)
+        + Vec3::new((t * 1.3).sin() * 0.002, (t * 2.6).sin() * 0.0015, 0.0) * amount;
+    transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        (t * 1.3).sin() * 0.004 * amount,
+        (t * 1.7).cos() * 0.003 * amount,
+        0.0,
+    );
+}
@@ -0,0 +1,118 @@
+//! Ballistic materials for level geometry, set from TrenchBroom.
+//!
+//! A texture picks its material with a `surface` property in its `.toml` file:
+//!
+//! ```toml
+//! [properties]
+//! surface = "Plywood"
+//! ```
+//!
+//! A `ballistic_brush` entity's `surface` property overrides the materials of its textures.
+
+use avian_bullet_trajectory::BallisticMaterial;
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_material_property(GenericMaterial::SURFACE);
+    app.add_observer(apply_brush_surface);
+    app.add_observer(mark_unsurfaced_mesh);
+    app.add_systems(Update, apply_texture_surface);
+}
+
+/// What a surface is made of, as far as bullets are concerned
+#[derive(FgdType, Default, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceKind {
+    /// Stops every bullet
+    #[default]
+    Solid,
+    /// Boxes and targets, which barely slow a bullet down
+    Cardboard,
+    /// Interior walls that most rounds pass through
+    Drywall,
+    /// Windows, which bullets pass through and glance off at shallow angles
+    Glass,
+    /// Wooden boards and crates that pistol rounds can get through
+    Plywood,
+    /// Walls and floors that only heavy rounds get far into
+    Concrete,
+    /// Armor plate, which stops bullets and sends grazing ones off as ricochets
+    Steel,
+}
+
+impl SurfaceKind {
+    /// The ballistic material bullets see when hitting this surface
+    pub fn material(&self) -> BallisticMaterial {
+        match self {
+            SurfaceKind::Solid => BallisticMaterial::default(),
+            SurfaceKind::Cardboard => BallisticMaterial::cardboard(),
+            SurfaceKind::Drywall => BallisticMaterial::drywall(),
+            SurfaceKind::Glass => BallisticMaterial::glass(),
+            SurfaceKind::Plywood => BallisticMaterial::plywood(),
+            SurfaceKind::Concrete => BallisticMaterial::concrete(),
+            SurfaceKind::Steel => BallisticMaterial::steel(),
+        }
+    }
+}
+
+pub trait SurfaceMaterialProperties {
+    /// The [`SurfaceKind`] of brushes using the texture
+    const SURFACE: MaterialProperty<SurfaceKind> = MaterialProperty::new("surface");
+}
+
+impl SurfaceMaterialProperties for GenericMaterial {}
+
+/// Brushes made of a single ballistic material, like a plywood wall or a steel plate
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+#[reflect(Component)]
+pub struct BallisticBrush {
+    pub surface: SurfaceKind,
+}
+
+fn apply_brush_surface(
+    event: On<Add, BallisticBrush>,
+    brushes: Query<&BallisticBrush>,
+    mut commands: Commands,
+) {
+    let entity = event.entity;
+    if let Ok(brush) = brushes.get(entity) {
+        tracing::info!(?entity, surface = ?brush.surface, "Setting Up Ballistic Brush");
+        commands.entity(entity).insert(brush.surface.material());
+    }
+}
+
+/// Mesh whose texture's `surface` property hasn't been applied to its brush yet
+#[derive(Component, Debug, Clone, Copy)]
+struct UnsurfacedMesh;
+
+fn mark_unsurfaced_mesh(event: On<Add, GenericMaterial3d>, mut commands: Commands) {
+    commands.entity(event.entity).insert(UnsurfacedMesh);
+}
+
+/// Gives a brush entity the material of its textures' `surface` property, unless it already has one
+///
+/// Textures may still be loading when the map spawns, so meshes are checked again every frame
+/// until their texture is in.
+fn apply_texture_surface(
+    meshes: Query<(Entity, &GenericMaterial3d, Option<&ChildOf>), With<UnsurfacedMesh>>,
+    surfaced: Query<(), Or<(With<BallisticMaterial>, With<BallisticBrush>)>>,
+    generic_materials: Res<Assets<GenericMaterial>>,
+    mut commands: Commands,
+) {
+    for (mesh, material, child_of) in &meshes {
+        // The colliders are on the brush entity, the meshes are its children
+        let brush = child_of
+            .map(ChildOf::parent)
+            .filter(|brush| !surfaced.contains(*brush));
+        if let Some(brush) = brush {
+            let Some(material) = generic_materials.get(&material.0) else {
+                continue;
+            };
+            if let Ok(surface) = material.get_property(GenericMaterial::SURFACE) {
+                commands.entity(brush).insert(surface.material());
+            }
+        }
+        commands.entity(mesh).remove::<UnsurfacedMesh>();
+    }
+}
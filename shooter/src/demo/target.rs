@@ -1,10 +1,22 @@
-use crate::theme::widget;
+use crate::{audio::sound_effect, theme::widget};
 
-use super::debug::DebugLines;
-use avian_bullet_trajectory::BulletTrajectory;
+use super::{
+    ammo::AmmoPickup,
+    debug::DebugLines,
+    firing::WeaponFired,
+    layers::GameLayer,
+    recoil::Bloom,
+    weapon::{VIEWMODEL_POSITION, Weapon, WeaponDefinition, ZeroElevation},
+};
+use avian_bullet_trajectory::{
+    BallisticMaterial, BallisticMaterials, BulletImpact, BulletPhysicsConfig, BulletSpatialQuery,
+    BulletTrajectory, DamageModel, EnergyDamage, Hitbox, ImpactImpulsePlugin, SpreadPattern,
+    TrajectoryRecording,
+};
 use avian3d::prelude::*;
 use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
 use bevy_enhanced_input::prelude::*;
+use bevy_tnua::prelude::TnuaController;
 use bevy_trenchbroom::prelude::*;
 use bevy_ui_anchor::{
     AnchorPoint, AnchorUiConfig, AnchorUiNode, AnchoredUiNodes, HorizontalAnchor, VerticalAnchor,
@@ -19,12 +31,28 @@ struct Target;
 #[reflect(Component)]
 struct TargetSpawner;
 
+/// Hit points a target has left before it is knocked down
+#[derive(Component, Debug, Clone, Copy)]
+struct Health(f32);
+
+const TARGET_HEALTH: f32 = 100.0;
+
+/// A 9mm round does about 50 damage, a 5.56 round about 160
+const BULLET_DAMAGE: EnergyDamage = EnergyDamage {
+    damage_per_joule: 0.1,
+    minimum_energy: 80.0,
+};
+
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(ImpactImpulsePlugin);
     app.add_observer(setup_target);
-    app.add_systems(Update, (handle_click, spawn_target));
+    app.add_observer(shoot);
+    app.add_observer(debug_shot);
+    app.add_systems(Update, spawn_target);
     app.add_input_context::<WeaponContext>();
     app.add_observer(apply_weapon_binding);
     app.add_observer(remove_weapon_binding);
+    app.add_observer(pickup_nearest);
     app.add_observer(pickup_weapon);
     app.add_systems(Update, update_target_distances);
 }
@@ -35,6 +63,9 @@ fn setup_target(event: On<Add, Target>, mut commands: Commands, asset_server: Re
     commands.entity(entity).insert((
         RigidBody::Static,
         Collider::cuboid(1.0, 1.0, 0.6),
+        CollisionLayers::new(GameLayer::Enemy, LayerMask::ALL),
+        BallisticMaterial::cardboard(),
+        Health(TARGET_HEALTH),
         SceneRoot(asset_server.load("models/target.gltf#Scene0")),
         AnchoredUiNodes::spawn_one((
             AnchorUiConfig {
@@ -51,44 +82,135 @@ fn setup_target(event: On<Add, Target>, mut commands: Commands, asset_server: Re
     ));
 }
 
-fn handle_click(
-    mouse: Res<ButtonInput<MouseButton>>,
+/// Fires a round from the held weapon
+fn shoot(
+    _event: On<WeaponFired>,
+    time: Res<Time>,
     origin: Single<&Transform, With<Camera3d>>,
-    spatial_query: SpatialQuery,
-    targets: Query<&Target>,
-    weapon: Single<&super::player::WeaponType>,
+    spatial_query: BulletSpatialQuery,
+    materials: BallisticMaterials,
+    environment: Res<super::level::LevelEnvironment>,
+    mut targets: Query<(&Target, &mut Health, Option<&Hitbox>)>,
+    weapon: Single<(&Weapon, &ZeroElevation, &mut Bloom)>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    asset_server: Res<AssetServer>,
+    player: Single<(Entity, &LinearVelocity, &TnuaController), With<super::player::Player>>,
     mut commands: Commands,
     mut lines: ResMut<DebugLines>,
+    mut shots: Local<u64>,
 ) {
-    if mouse.just_pressed(MouseButton::Left) {
-        // Bullet starts slightly in front of camera to avoid self-collision
-        let start = origin.translation + origin.forward() * 2.0;
-        let direction = origin.forward();
+    let (weapon, elevation, mut bloom) = weapon.into_inner();
+    let Some(weapon) = definitions.get(&weapon.0) else {
+        return;
+    };
+    let (player, velocity, controller) = *player;
+    // Bullets can't hit the player who fired them, pickups or triggers
+    let filter = GameLayer::bullet_filter(player);
 
-        let initial_velocity = direction * weapon.muzzle_velocity();
-        let bullet_mass = 0.0075; // 7.5 grams for 9mm
+    if let Some(sound) = &weapon.sounds.fire {
+        commands.spawn(sound_effect(asset_server.load(sound.clone())));
+    }
+    let bullet_mass = weapon.bullet_mass;
+
+    // Fire from the muzzle below the sights, tilted up to hit the point of aim at the zero
+    let (start, direction) = weapon.zeroing().launch(&origin, **elevation);
 
-        // Use realistic physics config for 9mm
-        let config = weapon.ballistics();
-        let filter = SpatialQueryFilter::default();
+    // Stray from the point of aim more while moving, jumping or firing in quick succession
+    let cone = weapon.accuracy.cone(
+        Vec2::new(velocity.x, velocity.z).length(),
+        controller.is_airborne().unwrap_or(false),
+        **bloom,
+    );
+    // Seeded by the shot count, like the pellet pattern below
+    *shots += 1;
+    let initial_velocity = SpreadPattern::perturb(direction, cone, *shots) * weapon.muzzle_velocity;
+    **bloom = (**bloom + weapon.accuracy.bloom).min(weapon.accuracy.max_bloom);
 
-        // Simulate the bullet trajectory
-        let trajectory = spatial_query.simulate_bullet_trajectory(
+    // Use realistic physics config for the weapon in the level's air
+    let config = BulletPhysicsConfig {
+        environment: environment.0.clone(),
+        // Gusts move on between shots
+        launch_time: time.elapsed_secs(),
+        // Only keep enough of the path to draw it
+        recording: TrajectoryRecording::Simplified { tolerance: 0.01 },
+        ..weapon.ballistics()
+    };
+
+    // Simulate the bullet trajectory, bouncing off or passing through materials. Shotguns
+    // simulate all their pellets at once, with a new pattern every shot
+    let trajectories = match weapon.spread(*shots) {
+        Some(spread) => spatial_query.par_simulate_spread(
+            start,
+            initial_velocity,
+            bullet_mass,
+            &spread,
+            Some(config),
+            &filter,
+            Some(&materials),
+        ),
+        None => vec![spatial_query.simulate_bullet_with_materials(
             start,
             initial_velocity,
             bullet_mass,
             Some(config),
             &filter,
-        );
+            &materials,
+        )],
+    };
+
+    for trajectory in trajectories {
+        // Knock around any loose props the bullet hit
+        for impact in BulletImpact::from_trajectory(&trajectory, bullet_mass) {
+            commands.trigger(impact);
+        }
+
+        // Targets that were shot clean through
+        for penetration in trajectory.penetrations.iter() {
+            if let Ok((_, mut health, hitbox)) = targets.get_mut(penetration.entity) {
+                info!(
+                    "Shot through target: {:?} at {:?}",
+                    penetration.entity, penetration.entry_point
+                );
+                // The target takes the energy the bullet lost passing through it
+                let energy = 0.5
+                    * bullet_mass
+                    * (penetration.entry_velocity.length_squared()
+                        - penetration.exit_velocity.length_squared());
+                let damage = BULLET_DAMAGE.hitbox_damage(energy, hitbox);
+                damage_target(&mut commands, penetration.entity, &mut health, damage);
+            }
+
+            // Draw exit points in orange
+            let exit_point = penetration.exit_point;
+            lines.push(move |gizmos: &mut Gizmos| {
+                gizmos.sphere(exit_point, 0.1, Color::linear_rgb(1.0, 0.5, 0.0));
+            });
+        }
+
+        // Draw ricochets as the bounce point and surface normal in magenta
+        for ricochet in trajectory.ricochets.iter() {
+            let (point, normal) = (ricochet.point, ricochet.normal);
+            lines.push(move |gizmos: &mut Gizmos| {
+                gizmos.sphere(point, 0.1, Color::linear_rgb(1.0, 0.0, 1.0));
+                gizmos.arrow(
+                    point,
+                    point + normal * 0.5,
+                    Color::linear_rgb(1.0, 0.0, 1.0),
+                );
+            });
+        }
 
         // Check if we hit a target
         if let Some(hit_entity) = trajectory.hit_entity {
-            if let Ok(target) = targets.get(hit_entity) {
+            if let Ok((target, mut health, hitbox)) = targets.get_mut(hit_entity) {
                 info!("Hit target: {:?} at {:?}", target, trajectory.hit_point);
                 info!(
-                    "Impact velocity: {:.1} m/s",
-                    trajectory.impact_velocity.length()
+                    "Impact velocity: {:.1} m/s, energy: {:.0} J",
+                    trajectory.impact_velocity.length(),
+                    trajectory.impact_energy
                 );
+                let damage = BULLET_DAMAGE.hitbox_damage(trajectory.impact_energy, hitbox);
+                damage_target(&mut commands, hit_entity, &mut health, damage);
 
                 // Draw successful hit trajectory in green
                 let points = trajectory.trajectory_points.clone();
@@ -99,8 +221,6 @@ fn handle_click(
                     // Draw impact point
                     gizmos.sphere(trajectory.hit_point, 0.2, Color::linear_rgb(1.0, 1.0, 0.0));
                 });
-
-                commands.entity(hit_entity).despawn();
             } else {
                 // Hit something else - draw in red
                 let points = trajectory.trajectory_points.clone();
@@ -122,38 +242,59 @@ fn handle_click(
             });
         }
     }
+}
 
-    // Right-click for simple trajectory without air resistance
-    if mouse.just_pressed(MouseButton::Right) {
-        let start = origin.translation + origin.forward() * 2.0;
-        let direction = origin.forward();
-        let initial_velocity = direction * 900.0; // Faster for demo
-        let filter = SpatialQueryFilter::default();
+/// Fires a simple trajectory without air resistance, to compare against the weapon's
+fn debug_shot(
+    _trigger: On<Fire<DebugShot>>,
+    origin: Single<&Transform, With<Camera3d>>,
+    spatial_query: BulletSpatialQuery,
+    mut targets: Query<&mut Health, With<Target>>,
+    player: Single<Entity, With<super::player::Player>>,
+    mut commands: Commands,
+    mut lines: ResMut<DebugLines>,
+) {
+    // Bullets can't hit the player who fired them, pickups or triggers
+    let filter = GameLayer::bullet_filter(*player);
 
-        let trajectory = spatial_query.simulate_simple_trajectory(
-            start,
-            initial_velocity,
-            None, // Use default gravity
-            &filter,
-        );
-
-        // Draw simple trajectory in cyan
-        let points = trajectory.trajectory_points.clone();
-        let hit_point = trajectory.hit_point;
-        lines.push(move |gizmos: &mut Gizmos| {
-            for window in points.windows(2) {
-                gizmos.line(window[0], window[1], Color::linear_rgb(0.0, 1.0, 1.0));
-            }
-            if trajectory.hit_entity.is_some() {
-                gizmos.sphere(hit_point, 0.2, Color::linear_rgb(1.0, 0.0, 1.0));
-            }
-        });
+    let start = origin.translation;
+    let direction = origin.forward();
+    let initial_velocity = direction * 900.0; // Faster for demo
 
-        if let Some(hit_entity) = trajectory.hit_entity
-            && targets.get(hit_entity).is_ok()
-        {
-            commands.entity(hit_entity).despawn();
+    let trajectory = spatial_query.simulate_simple_trajectory(
+        start,
+        initial_velocity,
+        None, // Use default gravity
+        &filter,
+    );
+
+    // Draw simple trajectory in cyan
+    let points = trajectory.trajectory_points.clone();
+    let hit_point = trajectory.hit_point;
+    lines.push(move |gizmos: &mut Gizmos| {
+        for window in points.windows(2) {
+            gizmos.line(window[0], window[1], Color::linear_rgb(0.0, 1.0, 1.0));
         }
+        if trajectory.hit_entity.is_some() {
+            gizmos.sphere(hit_point, 0.2, Color::linear_rgb(1.0, 0.0, 1.0));
+        }
+    });
+
+    // The debug round has no mass to work out damage from, so it knocks targets down outright
+    if let Some(hit_entity) = trajectory.hit_entity
+        && let Ok(mut health) = targets.get_mut(hit_entity)
+    {
+        damage_target(&mut commands, hit_entity, &mut health, TARGET_HEALTH);
+    }
+}
+
+/// Damages a target, knocking it down once it runs out of health
+fn damage_target(commands: &mut Commands, entity: Entity, health: &mut Health, damage: f32) {
+    health.0 -= damage;
+    info!(?entity, damage, health = health.0, "Damaged target");
+    if health.0 <= 0.0 {
+        // Pellets of the same shot can hit the same target
+        commands.entity(entity).try_despawn();
     }
 }
 
@@ -173,17 +314,66 @@ fn spawn_target(
 #[action_output(bool)]
 pub(super) struct Pickup;
 
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(super) struct Reload;
+
+/// Pulls the trigger of the held weapon
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(super) struct Shoot;
+
+/// Raises the sights of the held weapon while held
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(super) struct Aim;
+
+/// Switches the held weapon to its next fire mode
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(super) struct SelectFireMode;
+
+/// Fires a debug round along a simple trajectory without air resistance
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(super) struct DebugShot;
+
 #[derive(Component)]
 pub(super) struct WeaponContext;
 
 impl WeaponContext {
     fn bindings() -> impl Bundle {
         actions!(
-            WeaponContext[(
-                Action::<Pickup>::new(),
-                bevy_enhanced_input::prelude::Press::new(1.0),
-                bindings![KeyCode::KeyF]
-            )]
+            WeaponContext[
+                (
+                    Action::<Pickup>::new(),
+                    bevy_enhanced_input::prelude::Press::new(1.0),
+                    bindings![KeyCode::KeyF]
+                ),
+                (
+                    Action::<Reload>::new(),
+                    bevy_enhanced_input::prelude::Press::new(1.0),
+                    bindings![KeyCode::KeyR]
+                ),
+                (
+                    Action::<Shoot>::new(),
+                    bindings![MouseButton::Left]
+                ),
+                (
+                    Action::<Aim>::new(),
+                    bindings![MouseButton::Right]
+                ),
+                (
+                    Action::<SelectFireMode>::new(),
+                    bevy_enhanced_input::prelude::Press::new(1.0),
+                    bindings![KeyCode::KeyB]
+                ),
+                (
+                    Action::<DebugShot>::new(),
+                    bevy_enhanced_input::prelude::Press::new(1.0),
+                    bindings![MouseButton::Middle]
+                )
+            ]
         )
     }
 }
@@ -208,49 +398,90 @@ fn remove_weapon_binding(
     });
 }
 
-fn pickup_weapon(
+/// The player picked something up: a weapon off its spawner or a box of ammo
+#[derive(EntityEvent, Debug, Clone, Copy, PartialEq)]
+pub struct PickedUp {
+    /// The body of the pickup
+    pub entity: Entity,
+}
+
+/// Picks up the nearest weapon or ammo for the held weapon in reach, one per press
+fn pickup_nearest(
     _trigger: On<Fire<Pickup>>,
     mut commands: Commands,
     mut lines: ResMut<DebugLines>,
     spatial_query: SpatialQuery,
-    asset_server: Res<AssetServer>,
-    weapons: Query<&super::player::WeaponSpawner>,
-    existing_weapons: Query<Entity, With<super::player::WeaponType>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    pickup_areas: Query<&ColliderOf>,
+    pickups: Query<(
+        &GlobalTransform,
+        Option<&super::player::WeaponSpawnerDefinition>,
+        Option<&AmmoPickup>,
+    )>,
+    held: Option<Single<&Weapon>>,
     player: Single<&Transform, With<super::player::Player>>,
-    player_view: Single<Entity, With<super::player::PlayerView>>,
 ) {
-    info!("Picking up weapon");
     let location = player.translation;
-    if let Some(weapon) = spatial_query
+    let caliber = held
+        .and_then(|weapon| definitions.get(&weapon.0))
+        .map(|definition| definition.caliber);
+    let nearest = spatial_query
         .shape_intersections(
             &Collider::sphere(2.0),
             location,
             Quat::default(),
-            &SpatialQueryFilter::default(),
+            &SpatialQueryFilter::from_mask(GameLayer::Pickup),
         )
-        .iter()
-        .filter_map(|entity| weapons.get(*entity).map(|weapon| weapon.weapon).ok())
-        .next()
-    {
-        info!(weapon = weapon.name(), "Weapon Found");
-        lines.push(move |gizmos| {
-            gizmos.sphere(location, 2.0, Color::linear_rgb(0.0, 1.0, 0.0));
-        });
-        existing_weapons.iter().for_each(|entity| {
-            commands.entity(entity).try_despawn();
-        });
-        commands.entity(*player_view).insert(children![(
-            Name::new(weapon.name().to_string()),
-            SceneRoot(asset_server.load(weapon.model())),
-            Transform::from_xyz(0.08, -0.12, -0.3),
-            NoFrustumCulling,
-            weapon
-        )]);
-    } else {
-        lines.push(move |gizmos| {
-            gizmos.sphere(location, 2.0, Color::WHITE);
-        });
-    }
+        .into_iter()
+        // Weapons' pickup areas are colliders of their body, ammo boxes are colliders themselves
+        .map(|entity| pickup_areas.get(entity).map_or(entity, |area| area.body))
+        .filter_map(|entity| {
+            let (transform, weapon, ammo) = pickups.get(entity).ok()?;
+            let usable = weapon.is_some_and(|weapon| definitions.contains(&weapon.0))
+                || ammo.is_some_and(|ammo| Some(ammo.caliber) == caliber);
+            usable.then(|| (entity, transform.translation().distance_squared(location)))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    let color = match nearest {
+        Some((entity, _)) => {
+            commands.trigger(PickedUp { entity });
+            Color::linear_rgb(0.0, 1.0, 0.0)
+        }
+        None => Color::WHITE,
+    };
+    lines.push(move |gizmos| {
+        gizmos.sphere(location, 2.0, color);
+    });
+}
+
+/// Swaps the held weapon for the one on a spawner the player picked up
+fn pickup_weapon(
+    event: On<PickedUp>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    spawners: Query<&super::player::WeaponSpawnerDefinition>,
+    existing_weapons: Query<Entity, With<Weapon>>,
+    player_view: Single<Entity, With<super::player::PlayerView>>,
+) {
+    let Ok(weapon) = spawners.get(event.entity) else {
+        return;
+    };
+    let Some(definition) = definitions.get(&weapon.0) else {
+        return;
+    };
+    info!(weapon = definition.name, "Picking up weapon");
+    existing_weapons.iter().for_each(|entity| {
+        commands.entity(entity).try_despawn();
+    });
+    commands.entity(*player_view).insert(children![(
+        Name::new(definition.name.clone()),
+        SceneRoot(asset_server.load(definition.model.clone())),
+        Transform::from_translation(VIEWMODEL_POSITION),
+        NoFrustumCulling,
+        Weapon(weapon.0.clone())
+    )]);
 }
 
 fn update_target_distances(
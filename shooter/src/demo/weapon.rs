@@ -0,0 +1,346 @@
+//! Weapons, defined by `.weapon.ron` files in `assets/weapons/`.
+//!
+//! A weapon's id is its file name without the extension, so `assets/weapons/glock.weapon.ron`
+//! defines the `glock`. The files hot-reload in native dev builds.
+
+use avian_bullet_trajectory::{BulletPhysicsConfig, SpreadPattern, Zeroing};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_trenchbroom::prelude::*;
+use serde::Deserialize;
+
+use super::{aim::AimBlend, firing::FireControl, player::WeaponSpawnerDefinition, recoil::Bloom};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<WeaponDefinition>();
+    app.init_asset_loader::<WeaponDefinitionLoader>();
+    app.add_observer(zero_weapon);
+    app.add_systems(Update, reload_weapon_models);
+}
+
+/// Where the held weapon rests relative to the view
+pub const VIEWMODEL_POSITION: Vec3 = Vec3::new(0.08, -0.12, -0.3);
+
+/// Asset path of the weapon with the given id
+pub fn definition_path(id: &str) -> String {
+    format!("weapons/{id}.weapon.ron")
+}
+
+/// Everything that sets one weapon apart from another
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct WeaponDefinition {
+    /// Name shown to the player
+    pub name: String,
+    /// Scene of the weapon, used for the pickup and the viewmodel
+    pub model: String,
+    pub caliber: Caliber,
+    /// Mass of a single bullet or pellet in kg
+    pub bullet_mass: f32,
+    /// Speed of the bullet as it leaves the muzzle in m/s
+    pub muzzle_velocity: f32,
+    /// Height of the sights above the bore in meters
+    pub sight_height: f32,
+    /// Distance the sights are zeroed at in meters
+    pub zero_distance: f32,
+    /// How the pellets of a shot are scattered, for weapons that fire more than one
+    #[serde(default)]
+    pub pellets: Option<Pellets>,
+    /// Fire modes the selector cycles through, starting with the first
+    pub fire_modes: Vec<FireMode>,
+    /// Fastest the weapon cycles
+    pub rounds_per_minute: f32,
+    /// Rounds a full magazine holds, not counting the one in the chamber
+    pub magazine_size: u32,
+    /// Spare rounds the weapon comes with
+    pub reserve: u32,
+    /// Seconds to swap magazines with a round still chambered
+    pub reload_time: f32,
+    /// Seconds to swap magazines and chamber a new round once the weapon runs dry
+    pub empty_reload_time: f32,
+    #[serde(default)]
+    pub recoil: Recoil,
+    #[serde(default)]
+    pub accuracy: Accuracy,
+    #[serde(default)]
+    pub sights: Sights,
+    #[serde(default)]
+    pub sounds: WeaponSounds,
+}
+
+impl WeaponDefinition {
+    pub fn ballistics(&self) -> BulletPhysicsConfig {
+        self.caliber.ballistics()
+    }
+
+    pub fn zeroing(&self) -> Zeroing {
+        Zeroing::new(self.sight_height, self.zero_distance)
+    }
+
+    /// Angle between the line of sight and the bore that zeroes the weapon, or level if the zero
+    /// is out of reach. Solving it takes hundreds of simulations, so it's kept in
+    /// [`ZeroElevation`] instead of solved for every shot.
+    pub fn zero_elevation(&self) -> f32 {
+        self.zeroing()
+            .elevation(self.muzzle_velocity, self.bullet_mass, &self.ballistics())
+            .unwrap_or_default()
+    }
+
+    /// The pellet pattern of a shot, for weapons that fire more than one
+    pub fn spread(&self, seed: u64) -> Option<SpreadPattern> {
+        self.pellets
+            .map(|pellets| SpreadPattern::new(pellets.cone_angle, pellets.count, seed))
+    }
+}
+
+/// Ballistics preset of the rounds a weapon fires
+#[derive(FgdType, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Caliber {
+    /// 9x19mm Parabellum
+    #[default]
+    Parabellum9mm,
+    /// 5.56x45mm NATO
+    Nato556,
+    /// 7.62x51mm NATO
+    Nato762,
+    /// .50 BMG
+    Bmg50,
+    /// 12 gauge 00 buckshot
+    Buckshot00,
+}
+
+impl Caliber {
+    pub fn ballistics(&self) -> BulletPhysicsConfig {
+        match self {
+            Caliber::Parabellum9mm => BulletPhysicsConfig::caliber_9mm(),
+            Caliber::Nato556 => BulletPhysicsConfig::caliber_556(),
+            Caliber::Nato762 => BulletPhysicsConfig::caliber_762(),
+            Caliber::Bmg50 => BulletPhysicsConfig::caliber_50bmg(),
+            Caliber::Buckshot00 => BulletPhysicsConfig::buckshot_00(),
+        }
+    }
+}
+
+/// Pellets fired per shot by a shotgun
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Pellets {
+    pub count: usize,
+    /// Angle between the aim direction and the edge of the cone in radians
+    pub cone_angle: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum FireMode {
+    /// One shot per trigger pull
+    Semi,
+    /// A fixed number of shots per trigger pull
+    Burst(u32),
+    /// Shots for as long as the trigger is held
+    Auto,
+}
+
+/// How much a shot kicks the view, in radians
+///
+/// Weapons with a `pattern` kick along it, and `vertical` and `horizontal` are ignored. Weapons
+/// without one kick by `vertical` and `horizontal` instead.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default)]
+pub struct Recoil {
+    /// Upward kick per shot, for weapons without a pattern
+    pub vertical: f32,
+    /// Largest sideways kick per shot in either direction, for weapons without a pattern
+    pub horizontal: f32,
+    /// How fast the view settles back, in radians per second
+    pub recovery: f32,
+    /// Kick of each shot in a string as `(right, up)`, with the last one repeating
+    pub pattern: Vec<(f32, f32)>,
+}
+
+impl Recoil {
+    /// Kick of the `shot`th shot in a string, as `(right, up)`
+    ///
+    /// Follows the pattern if there is one. Otherwise kicks up by `vertical` and randomly
+    /// sideways by up to `horizontal`.
+    pub fn kick(&self, shot: usize) -> (f32, f32) {
+        match self.pattern.get(shot).or(self.pattern.last()) {
+            Some(&kick) => kick,
+            None => (
+                rand::random_range(-1.0..=1.0) * self.horizontal,
+                self.vertical,
+            ),
+        }
+    }
+}
+
+/// How far shots stray from the point of aim, as the half-angle of a cone in radians
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(default)]
+pub struct Accuracy {
+    /// Cone when standing still
+    pub standing: f32,
+    /// Extra cone per m/s the shooter is moving
+    pub moving: f32,
+    /// Extra cone while the shooter is in the air
+    pub airborne: f32,
+    /// Extra cone each shot adds, which wears off between shots
+    pub bloom: f32,
+    /// Largest extra cone from bloom
+    pub max_bloom: f32,
+    /// How fast bloom wears off, in radians per second
+    pub bloom_recovery: f32,
+}
+
+impl Accuracy {
+    /// Cone of a shot fired while moving at `speed` m/s with the given bloom
+    pub fn cone(&self, speed: f32, airborne: bool, bloom: f32) -> f32 {
+        let airborne = if airborne { self.airborne } else { 0.0 };
+        self.standing + self.moving * speed + airborne + bloom
+    }
+}
+
+/// How the weapon is brought up to the eye when aiming down its sights
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Sights {
+    /// Where the weapon rests relative to the view while aiming, as `(x, y, z)` in meters
+    pub offset: (f32, f32, f32),
+    /// Magnification while aiming through iron sights
+    pub zoom: f32,
+    /// Seconds to bring the sights up to the eye
+    pub aim_time: f32,
+    /// Optic that replaces the sights with a reticle once it's up, for scoped weapons
+    pub scope: Option<Scope>,
+}
+
+impl Default for Sights {
+    fn default() -> Self {
+        Self {
+            offset: (0.0, -0.08, -0.25),
+            zoom: 1.2,
+            aim_time: 0.2,
+            scope: None,
+        }
+    }
+}
+
+impl Sights {
+    /// Where the weapon rests relative to the view while aiming
+    pub fn position(&self) -> Vec3 {
+        Vec3::from(self.offset)
+    }
+
+    /// Magnification once the sights are fully up
+    pub fn magnification(&self) -> f32 {
+        self.scope.map_or(self.zoom, |scope| scope.magnification)
+    }
+}
+
+/// A magnifying optic with holdover marks below the crosshair
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Scope {
+    pub magnification: f32,
+    /// Distance between holdover marks in meters
+    pub mark_spacing: f32,
+    /// Range of the furthest holdover mark in meters
+    pub max_range: f32,
+}
+
+/// Asset paths of a weapon's sounds
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default)]
+pub struct WeaponSounds {
+    pub fire: Option<String>,
+    pub dry_fire: Option<String>,
+    pub reload: Option<String>,
+}
+
+#[derive(Default, TypePath)]
+struct WeaponDefinitionLoader;
+
+impl AssetLoader for WeaponDefinitionLoader {
+    type Asset = WeaponDefinition;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["weapon.ron"]
+    }
+}
+
+/// The weapon the player is holding
+///
+/// State that comes from the definition, like the zero, is built when this is inserted, and
+/// inserted again when the definition hot-reloads. State that carries over, like the rounds
+/// left, is set up when it's added.
+#[derive(Component, Debug, Clone, Deref)]
+#[require(FireControl, Bloom, AimBlend, ZeroElevation)]
+pub struct Weapon(pub Handle<WeaponDefinition>);
+
+/// Angle in radians between the line of sight and the bore of the held weapon, from
+/// [`WeaponDefinition::zero_elevation`]
+#[derive(Component, Debug, Clone, Copy, Default, Deref)]
+pub struct ZeroElevation(pub f32);
+
+fn zero_weapon(
+    event: On<Insert, Weapon>,
+    weapons: Query<&Weapon>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    mut commands: Commands,
+) {
+    if let Ok(weapon) = weapons.get(event.entity)
+        && let Some(definition) = definitions.get(&weapon.0)
+    {
+        commands
+            .entity(event.entity)
+            .insert(ZeroElevation(definition.zero_elevation()));
+    }
+}
+
+/// Swaps the models of spawners and held weapons when their definition changes on disk, and
+/// inserts held weapons again so everything built from their definition is rebuilt
+fn reload_weapon_models(
+    mut events: MessageReader<AssetEvent<WeaponDefinition>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut held: Query<(Entity, &Weapon, &mut SceneRoot, &mut Name)>,
+    mut spawners: Query<(&WeaponSpawnerDefinition, &mut SceneRoot), Without<Weapon>>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = *event else {
+            continue;
+        };
+        let Some(definition) = definitions.get(id) else {
+            continue;
+        };
+        tracing::info!(weapon = definition.name, "Reloading Weapon Definition");
+        let model: Handle<Scene> = asset_server.load(definition.model.clone());
+        for (entity, weapon, mut scene, mut name) in &mut held {
+            if weapon.id() == id {
+                // Changing the scene respawns it
+                if scene.0 != model {
+                    scene.0 = model.clone();
+                }
+                *name = Name::new(definition.name.clone());
+                commands.entity(entity).insert(weapon.clone());
+            }
+        }
+        for (spawner, mut scene) in &mut spawners {
+            if spawner.id() == id && scene.0 != model {
+                scene.0 = model.clone();
+            }
+        }
+    }
+}